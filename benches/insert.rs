@@ -0,0 +1,52 @@
+//! Times inserting 100k string pairs into a `BiHashMap`.
+//!
+//! Run with `cargo bench --bench insert`. Besides `insert` itself, this times
+//! `insert` preceded by the `contains_right` lookup it used to make.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use generic_bimap::BiHashMap;
+
+const PAIRS: usize = 100_000;
+const ROUNDS: usize = 10;
+
+fn pairs() -> Vec<(String, String)> {
+    (0..PAIRS)
+        .map(|i| (format!("left-{i}"), format!("right-{i}")))
+        .collect()
+}
+
+// Returns the fastest of several rounds, each given a fresh copy of the pairs.
+fn time(mut round: impl FnMut(Vec<(String, String)>)) -> Duration {
+    let pairs = pairs();
+    (0..ROUNDS)
+        .map(|_| {
+            let pairs = pairs.clone();
+            let start = Instant::now();
+            round(pairs);
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let insert = time(|pairs| {
+        let mut bimap: BiHashMap<String, String> = BiHashMap::new();
+        for (l, r) in pairs {
+            bimap.insert_unchecked(l, r);
+        }
+        black_box(bimap);
+    });
+    let contains_then_insert = time(|pairs| {
+        let mut bimap: BiHashMap<String, String> = BiHashMap::new();
+        for (l, r) in pairs {
+            black_box(bimap.contains_right(&r));
+            bimap.insert_unchecked(l, r);
+        }
+        black_box(bimap);
+    });
+    println!("insert:                {insert:?}");
+    println!("contains_right+insert: {contains_then_insert:?}");
+}
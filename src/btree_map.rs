@@ -0,0 +1,298 @@
+use alloc::collections::btree_map;
+use core::borrow::Borrow;
+use core::iter::FusedIterator;
+use core::mem;
+use core::ops::RangeBounds;
+
+use crate::{
+    BiMap, MapBase, MapExt, MapKind, NaturalMap, OrderedMapBase, OrderedMapExt, Ref,
+    TryReserveError, Wrapper,
+};
+
+pub struct BTreeMapKind {
+    _private: (),
+}
+
+impl<K, V> MapKind<K, V> for BTreeMapKind
+where
+    K: Ord,
+{
+    type Map = BTreeMap<K, V>;
+}
+
+pub struct BTreeMap<K, V> {
+    map: btree_map::BTreeMap<Ref<K>, Ref<V>>,
+}
+
+impl<K, V> MapBase for BTreeMap<K, V>
+where
+    K: Ord,
+{
+    type Key = K;
+    type Val = V;
+    type Iter<'a, K_: 'a, V_: 'a> = Iter<'a, K_, V_> where Self: 'a;
+    type IntoIter<K_, V_> = IntoIter<K_, V_>;
+    type Drain<'a, K_: 'a, V_: 'a> = IntoIter<K_, V_> where Self: 'a;
+
+    fn new() -> Self {
+        Self {
+            map: btree_map::BTreeMap::new(),
+        }
+    }
+
+    fn with_capacity(_capacity: usize) -> Self {
+        Self::new()
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    // B-trees allocate per node, so there is no spare capacity to manage.
+    fn capacity(&self) -> usize {
+        self.map.len()
+    }
+
+    fn reserve(&mut self, _additional: usize) {}
+
+    fn try_reserve(&mut self, _additional: usize) -> Result<(), TryReserveError> {
+        Ok(())
+    }
+
+    fn shrink_to_fit(&mut self) {}
+
+    fn shrink_to(&mut self, _min_capacity: usize) {}
+
+    fn iter(&self) -> Self::Iter<'_, Self::Key, Self::Val> {
+        Iter {
+            iter: self.map.iter(),
+        }
+    }
+
+    fn insert(&mut self, key: Ref<Self::Key>, val: Ref<Self::Val>) {
+        self.map.insert(key, val);
+    }
+
+    fn extend_pairs<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (Ref<Self::Key>, Ref<Self::Val>)>,
+    {
+        // Collecting into an empty map builds the tree in bulk.
+        if self.map.is_empty() {
+            self.map = iter.into_iter().collect();
+        } else {
+            self.map.extend(iter);
+        }
+    }
+
+    fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Self::Key, &Self::Val) -> bool,
+    {
+        self.map.retain(|k, v| f(k, v));
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    fn drain(&mut self) -> Self::Drain<'_, Self::Key, Self::Val> {
+        IntoIter {
+            iter: mem::take(&mut self.map).into_iter(),
+        }
+    }
+
+    fn into_iter(self) -> Self::IntoIter<Self::Key, Self::Val> {
+        IntoIter {
+            iter: self.map.into_iter(),
+        }
+    }
+}
+
+impl<K, V, Q: ?Sized> MapExt<Q> for BTreeMap<K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord,
+{
+    fn get(&self, key: &Q) -> Option<&Ref<Self::Val>> {
+        self.map.get(Wrapper::wrap(key))
+    }
+
+    fn get_key_value(&self, key: &Q) -> Option<(&Ref<Self::Key>, &Ref<Self::Val>)> {
+        self.map.get_key_value(Wrapper::wrap(key))
+    }
+
+    fn contains(&self, key: &Q) -> bool {
+        self.map.contains_key(Wrapper::wrap(key))
+    }
+
+    fn remove(&mut self, key: &Q) -> Option<(Ref<Self::Key>, Ref<Self::Val>)> {
+        self.map.remove_entry(Wrapper::wrap(key))
+    }
+}
+
+impl<K: Ord, V> NaturalMap for BTreeMap<K, V> {}
+
+impl<K, V> OrderedMapBase for BTreeMap<K, V>
+where
+    K: Ord,
+{
+    type Range<'a, K_: 'a, V_: 'a> = Range<'a, K_, V_> where Self: 'a;
+
+    fn first(&self) -> Option<(&Ref<Self::Key>, &Ref<Self::Val>)> {
+        self.map.first_key_value()
+    }
+
+    fn last(&self) -> Option<(&Ref<Self::Key>, &Ref<Self::Val>)> {
+        self.map.last_key_value()
+    }
+
+    fn pop_first(&mut self) -> Option<(Ref<Self::Key>, Ref<Self::Val>)> {
+        self.map.pop_first()
+    }
+
+    fn pop_last(&mut self) -> Option<(Ref<Self::Key>, Ref<Self::Val>)> {
+        self.map.pop_last()
+    }
+}
+
+impl<K, V, Q: ?Sized> OrderedMapExt<Q> for BTreeMap<K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord,
+{
+    fn range<T>(&self, range: T) -> Self::Range<'_, Self::Key, Self::Val>
+    where
+        T: RangeBounds<Q>,
+    {
+        let start = range.start_bound().map(Wrapper::wrap);
+        let end = range.end_bound().map(Wrapper::wrap);
+        Range {
+            iter: self.map.range::<Wrapper<Q>, _>((start, end)),
+        }
+    }
+
+    fn split_off(&mut self, key: &Q) -> Self {
+        Self {
+            map: self.map.split_off(Wrapper::wrap(key)),
+        }
+    }
+}
+
+impl<'a, L, R> Extend<(&'a L, &'a R)> for BiMap<BTreeMap<L, R>, BTreeMap<R, L>>
+where
+    L: Clone + Ord + 'a,
+    R: Clone + Ord + 'a,
+{
+    fn extend<I: IntoIterator<Item = (&'a L, &'a R)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(l, r)| (l.clone(), r.clone())));
+    }
+}
+
+impl<'a, L, R> FromIterator<(&'a L, &'a R)> for BiMap<BTreeMap<L, R>, BTreeMap<R, L>>
+where
+    L: Clone + Ord + 'a,
+    R: Clone + Ord + 'a,
+{
+    fn from_iter<I: IntoIterator<Item = (&'a L, &'a R)>>(iter: I) -> Self {
+        let mut bimap = BiMap::new();
+        bimap.extend(iter);
+        bimap
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    iter: btree_map::Iter<'a, Ref<K>, Ref<V>>,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Iter {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a Ref<K>, &'a Ref<V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for Iter<'a, K, V> {}
+
+pub struct IntoIter<K, V> {
+    iter: btree_map::IntoIter<Ref<K>, Ref<V>>,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (Ref<K>, Ref<V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+
+pub struct Range<'a, K, V> {
+    iter: btree_map::Range<'a, Ref<K>, Ref<V>>,
+}
+
+impl<K, V> Clone for Range<'_, K, V> {
+    fn clone(&self) -> Self {
+        Range {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a Ref<K>, &'a Ref<V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<'a, K, V> FusedIterator for Range<'a, K, V> {}
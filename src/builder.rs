@@ -0,0 +1,106 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+use crate::{BiBTreeMap, BiHashMap, BiMap};
+
+/// A builder for configuring a `BiHashMap` before creating it.
+#[derive(Clone, Debug, Default)]
+pub struct BiMapBuilder<S1 = RandomState, S2 = RandomState> {
+    capacity: usize,
+    left_hasher: S1,
+    right_hasher: S2,
+}
+
+/// A builder for a `BiBTreeMap`, returned by [`BiMapBuilder::ordered`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OrderedBiMapBuilder {
+    _private: (),
+}
+
+impl BiMapBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a builder for a `BiBTreeMap` instead, which has no capacity or
+    /// hashers to configure. This takes no `self`, so that a capacity or
+    /// hasher can't be set and then silently dropped.
+    pub fn ordered() -> OrderedBiMapBuilder {
+        OrderedBiMapBuilder::default()
+    }
+}
+
+impl<S1, S2> BiMapBuilder<S1, S2> {
+    /// Sets the number of pairs the bimap can hold without reallocating.
+    pub fn capacity(self, capacity: usize) -> Self {
+        BiMapBuilder { capacity, ..self }
+    }
+
+    /// Sets the hasher used by the left map.
+    pub fn left_hasher<S>(self, left_hasher: S) -> BiMapBuilder<S, S2> {
+        BiMapBuilder {
+            capacity: self.capacity,
+            left_hasher,
+            right_hasher: self.right_hasher,
+        }
+    }
+
+    /// Sets the hasher used by the right map.
+    pub fn right_hasher<S>(self, right_hasher: S) -> BiMapBuilder<S1, S> {
+        BiMapBuilder {
+            capacity: self.capacity,
+            left_hasher: self.left_hasher,
+            right_hasher,
+        }
+    }
+
+    pub fn build<L, R>(self) -> BiHashMap<L, R, S1, S2>
+    where
+        L: Eq + Hash,
+        R: Eq + Hash,
+        S1: BuildHasher + Default,
+        S2: BuildHasher + Default,
+    {
+        BiMap::with_capacity_and_hasher(self.capacity, self.left_hasher, self.right_hasher)
+    }
+}
+
+impl OrderedBiMapBuilder {
+    pub fn build<L: Ord, R: Ord>(self) -> BiBTreeMap<L, R> {
+        BiMap::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn build_reserves_the_capacity() {
+        let bimap: BiHashMap<u32, u32> = BiMapBuilder::new().capacity(100).build();
+        assert!(bimap.capacity() >= 100);
+        assert!(bimap.is_empty());
+    }
+
+    #[test]
+    fn build_uses_the_given_hashers() {
+        let left = RandomState::new();
+        let right = RandomState::new();
+        let bimap: BiHashMap<u32, char> = BiMapBuilder::new()
+            .left_hasher(left.clone())
+            .right_hasher(right.clone())
+            .build();
+        assert_eq!(bimap.left_hasher().hash_one(7u32), left.hash_one(7u32));
+        assert_eq!(bimap.right_hasher().hash_one('x'), right.hash_one('x'));
+    }
+
+    #[test]
+    fn ordered_builds_a_btree_bimap() {
+        let mut bimap: BiBTreeMap<u32, char> = BiMapBuilder::ordered().build();
+        bimap.insert_unchecked(2, 'b');
+        bimap.insert_unchecked(1, 'a');
+        assert_eq!(bimap.iter().collect::<Vec<_>>(), [(&1, &'a'), (&2, &'b')]);
+    }
+}
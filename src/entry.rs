@@ -0,0 +1,377 @@
+use crate::{BiMap, Map, MapBase, Ref};
+
+/// A view into a single left value of a `BiMap`, which may be either vacant
+/// or occupied.
+pub enum LeftEntry<'a, LMap: MapBase, RMap> {
+    Occupied(OccupiedLeftEntry<'a, LMap, RMap>),
+    Vacant(VacantLeftEntry<'a, LMap, RMap>),
+}
+
+/// A left value that is present in a `BiMap`.
+pub struct OccupiedLeftEntry<'a, LMap: MapBase, RMap> {
+    bimap: &'a mut BiMap<LMap, RMap>,
+    key: LMap::Key,
+}
+
+/// A left value that is absent from a `BiMap`.
+pub struct VacantLeftEntry<'a, LMap: MapBase, RMap> {
+    bimap: &'a mut BiMap<LMap, RMap>,
+    key: LMap::Key,
+}
+
+/// A view into a single right value of a `BiMap`, which may be either vacant
+/// or occupied.
+pub enum RightEntry<'a, LMap, RMap: MapBase> {
+    Occupied(OccupiedRightEntry<'a, LMap, RMap>),
+    Vacant(VacantRightEntry<'a, LMap, RMap>),
+}
+
+/// A right value that is present in a `BiMap`.
+pub struct OccupiedRightEntry<'a, LMap, RMap: MapBase> {
+    bimap: &'a mut BiMap<LMap, RMap>,
+    key: RMap::Key,
+}
+
+/// A right value that is absent from a `BiMap`.
+pub struct VacantRightEntry<'a, LMap, RMap: MapBase> {
+    bimap: &'a mut BiMap<LMap, RMap>,
+    key: RMap::Key,
+}
+
+impl<L, R, LMap, RMap> BiMap<LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    pub fn left_entry(&mut self, left: L) -> LeftEntry<'_, LMap, RMap> {
+        if self.contains_left(&left) {
+            LeftEntry::Occupied(OccupiedLeftEntry {
+                bimap: self,
+                key: left,
+            })
+        } else {
+            LeftEntry::Vacant(VacantLeftEntry {
+                bimap: self,
+                key: left,
+            })
+        }
+    }
+
+    pub fn right_entry(&mut self, right: R) -> RightEntry<'_, LMap, RMap> {
+        if self.contains_right(&right) {
+            RightEntry::Occupied(OccupiedRightEntry {
+                bimap: self,
+                key: right,
+            })
+        } else {
+            RightEntry::Vacant(VacantRightEntry {
+                bimap: self,
+                key: right,
+            })
+        }
+    }
+
+    /// Returns the right value paired with `left`, first pairing it with the
+    /// result of `f` if `left` is absent. Fails without modifying the bimap if
+    /// the new right value is already paired with a different left value.
+    pub fn get_left_or_insert_with<F>(&mut self, left: L, f: F) -> Result<&R, (L, R)>
+    where
+        F: FnOnce() -> R,
+    {
+        self.left_entry(left).or_insert_with(f)
+    }
+}
+
+impl<'a, L, R, LMap, RMap> LeftEntry<'a, LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    pub fn key(&self) -> &L {
+        match self {
+            LeftEntry::Occupied(entry) => entry.key(),
+            LeftEntry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Returns the right value paired with the entry's left value, inserting
+    /// `right` if the entry is vacant. See [`VacantLeftEntry::insert`].
+    pub fn or_insert(self, right: R) -> Result<&'a R, (L, R)> {
+        match self {
+            LeftEntry::Occupied(entry) => Ok(entry.into_right()),
+            LeftEntry::Vacant(entry) => entry.insert(right),
+        }
+    }
+
+    /// Returns the right value paired with the entry's left value, inserting
+    /// the result of `f` if the entry is vacant. See
+    /// [`VacantLeftEntry::insert`].
+    pub fn or_insert_with<F>(self, f: F) -> Result<&'a R, (L, R)>
+    where
+        F: FnOnce() -> R,
+    {
+        match self {
+            LeftEntry::Occupied(entry) => Ok(entry.into_right()),
+            LeftEntry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+}
+
+impl<'a, L, R, LMap, RMap> OccupiedLeftEntry<'a, LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    pub fn key(&self) -> &L {
+        &self.key
+    }
+
+    pub fn get(&self) -> &R {
+        self.bimap.get_left(&self.key).expect("bimap invariant")
+    }
+
+    pub fn into_right(self) -> &'a R {
+        let bimap: &'a BiMap<LMap, RMap> = self.bimap;
+        bimap.get_left(&self.key).expect("bimap invariant")
+    }
+
+    /// Removes the entry's pair from the bimap, as with
+    /// [`BiMap::remove_left`].
+    pub fn remove(self) -> (L, R)
+    where
+        L: Clone,
+        R: Clone,
+    {
+        self.bimap.remove_left(&self.key).expect("bimap invariant")
+    }
+}
+
+impl<'a, L, R, LMap, RMap> VacantLeftEntry<'a, LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    pub fn key(&self) -> &L {
+        &self.key
+    }
+
+    pub fn into_key(self) -> L {
+        self.key
+    }
+
+    /// Pairs `right` with the entry's left value. Fails without modifying the
+    /// bimap if `right` is already paired with a different left value.
+    pub fn insert(self, right: R) -> Result<&'a R, (L, R)> {
+        if self.bimap.contains_right(&right) {
+            return Err((self.key, right));
+        }
+        let (l0, l1) = Ref::split(self.key);
+        let (r0, r1) = Ref::split(right);
+        // Hold on to an extra `Ref` to look the right value back up.
+        let key = l0.clone();
+        self.bimap.lmap.insert(l0, r0);
+        self.bimap.rmap.insert(r1, l1);
+        let bimap: &'a BiMap<LMap, RMap> = self.bimap;
+        Ok(bimap.get_left(&*key).expect("bimap invariant"))
+    }
+}
+
+impl<'a, L, R, LMap, RMap> RightEntry<'a, LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    pub fn key(&self) -> &R {
+        match self {
+            RightEntry::Occupied(entry) => entry.key(),
+            RightEntry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Returns the left value paired with the entry's right value, inserting
+    /// `left` if the entry is vacant. See [`VacantRightEntry::insert`].
+    pub fn or_insert(self, left: L) -> Result<&'a L, (L, R)> {
+        match self {
+            RightEntry::Occupied(entry) => Ok(entry.into_left()),
+            RightEntry::Vacant(entry) => entry.insert(left),
+        }
+    }
+
+    /// Returns the left value paired with the entry's right value, inserting
+    /// the result of `f` if the entry is vacant. See
+    /// [`VacantRightEntry::insert`].
+    pub fn or_insert_with<F>(self, f: F) -> Result<&'a L, (L, R)>
+    where
+        F: FnOnce() -> L,
+    {
+        match self {
+            RightEntry::Occupied(entry) => Ok(entry.into_left()),
+            RightEntry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+}
+
+impl<'a, L, R, LMap, RMap> OccupiedRightEntry<'a, LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    pub fn key(&self) -> &R {
+        &self.key
+    }
+
+    pub fn get(&self) -> &L {
+        self.bimap.get_right(&self.key).expect("bimap invariant")
+    }
+
+    pub fn into_left(self) -> &'a L {
+        let bimap: &'a BiMap<LMap, RMap> = self.bimap;
+        bimap.get_right(&self.key).expect("bimap invariant")
+    }
+
+    /// Removes the entry's pair from the bimap, as with
+    /// [`BiMap::remove_right`].
+    pub fn remove(self) -> (L, R)
+    where
+        L: Clone,
+        R: Clone,
+    {
+        self.bimap.remove_right(&self.key).expect("bimap invariant")
+    }
+}
+
+impl<'a, L, R, LMap, RMap> VacantRightEntry<'a, LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    pub fn key(&self) -> &R {
+        &self.key
+    }
+
+    pub fn into_key(self) -> R {
+        self.key
+    }
+
+    /// Pairs `left` with the entry's right value. Fails without modifying the
+    /// bimap if `left` is already paired with a different right value.
+    pub fn insert(self, left: L) -> Result<&'a L, (L, R)> {
+        if self.bimap.contains_left(&left) {
+            return Err((left, self.key));
+        }
+        let (l0, l1) = Ref::split(left);
+        let (r0, r1) = Ref::split(self.key);
+        // Hold on to an extra `Ref` to look the left value back up.
+        let key = r1.clone();
+        self.bimap.lmap.insert(l0, r0);
+        self.bimap.rmap.insert(r1, l1);
+        let bimap: &'a BiMap<LMap, RMap> = self.bimap;
+        Ok(bimap.get_right(&*key).expect("bimap invariant"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BiBTreeMap, LeftEntry, RightEntry};
+
+    #[test]
+    fn occupied_left_entry_reads_the_pair() {
+        let mut bimap: BiBTreeMap<_, _> = [(1, 'a')].into_iter().collect();
+        match bimap.left_entry(1) {
+            LeftEntry::Occupied(entry) => {
+                assert_eq!(entry.key(), &1);
+                assert_eq!(entry.get(), &'a');
+            }
+            LeftEntry::Vacant(_) => panic!("expected an occupied entry"),
+        }
+        assert_eq!(bimap.left_entry(1).or_insert('z'), Ok(&'a'));
+        assert_eq!(bimap.len(), 1);
+    }
+
+    #[test]
+    fn vacant_left_entry_inserts() {
+        let mut bimap: BiBTreeMap<_, _> = [(1, 'a')].into_iter().collect();
+        assert_eq!(bimap.left_entry(2).or_insert('b'), Ok(&'b'));
+        assert_eq!(bimap.left_entry(3).or_insert_with(|| 'c'), Ok(&'c'));
+        assert_eq!(bimap.get_right(&'b'), Some(&2));
+        assert_eq!(bimap.len(), 3);
+        assert!(bimap.is_valid());
+    }
+
+    #[test]
+    fn vacant_left_entry_rejects_a_paired_right_value() {
+        let mut bimap: BiBTreeMap<_, _> = [(1, 'a')].into_iter().collect();
+        assert_eq!(bimap.left_entry(2).or_insert('a'), Err((2, 'a')));
+        assert_eq!(bimap.get_left_or_insert_with(2, || 'a'), Err((2, 'a')));
+        assert_eq!(bimap.get_right(&'a'), Some(&1));
+        assert_eq!(bimap.len(), 1);
+    }
+
+    #[test]
+    fn occupied_left_entry_removes() {
+        let mut bimap: BiBTreeMap<_, _> = [(1, 'a'), (2, 'b')].into_iter().collect();
+        if let LeftEntry::Occupied(entry) = bimap.left_entry(1) {
+            assert_eq!(entry.remove(), (1, 'a'));
+        }
+        assert!(!bimap.contains_right(&'a'));
+        assert!(bimap.is_valid());
+    }
+
+    #[test]
+    fn get_left_or_insert_with_covers_every_case() {
+        let mut bimap: BiBTreeMap<_, _> = [(1, 'a')].into_iter().collect();
+        assert_eq!(
+            bimap.get_left_or_insert_with(1, || unreachable!()),
+            Ok(&'a')
+        );
+        assert_eq!(bimap.get_left_or_insert_with(2, || 'b'), Ok(&'b'));
+        assert_eq!(bimap.get_left_or_insert_with(3, || 'b'), Err((3, 'b')));
+        assert_eq!(bimap.len(), 2);
+        assert!(bimap.is_valid());
+    }
+
+    #[test]
+    fn occupied_right_entry_reads_the_pair() {
+        let mut bimap: BiBTreeMap<_, _> = [(1, 'a')].into_iter().collect();
+        match bimap.right_entry('a') {
+            RightEntry::Occupied(entry) => {
+                assert_eq!(entry.key(), &'a');
+                assert_eq!(entry.get(), &1);
+            }
+            RightEntry::Vacant(_) => panic!("expected an occupied entry"),
+        }
+        assert_eq!(bimap.right_entry('a').or_insert(9), Ok(&1));
+        assert_eq!(bimap.len(), 1);
+    }
+
+    #[test]
+    fn vacant_right_entry_inserts() {
+        let mut bimap: BiBTreeMap<_, _> = [(1, 'a')].into_iter().collect();
+        assert_eq!(bimap.right_entry('b').or_insert(2), Ok(&2));
+        assert_eq!(bimap.right_entry('c').or_insert_with(|| 3), Ok(&3));
+        assert_eq!(bimap.get_left(&2), Some(&'b'));
+        assert_eq!(bimap.len(), 3);
+        assert!(bimap.is_valid());
+    }
+
+    #[test]
+    fn vacant_right_entry_rejects_a_paired_left_value() {
+        let mut bimap: BiBTreeMap<_, _> = [(1, 'a')].into_iter().collect();
+        assert_eq!(bimap.right_entry('b').or_insert(1), Err((1, 'b')));
+        if let RightEntry::Vacant(entry) = bimap.right_entry('b') {
+            assert_eq!(entry.insert(1), Err((1, 'b')));
+        }
+        assert_eq!(bimap.get_left(&1), Some(&'a'));
+        assert_eq!(bimap.len(), 1);
+    }
+
+    #[test]
+    fn occupied_right_entry_removes() {
+        let mut bimap: BiBTreeMap<_, _> = [(1, 'a'), (2, 'b')].into_iter().collect();
+        if let RightEntry::Occupied(entry) = bimap.right_entry('a') {
+            assert_eq!(entry.remove(), (1, 'a'));
+        }
+        assert!(!bimap.contains_left(&1));
+        assert!(bimap.is_valid());
+    }
+}
@@ -4,7 +4,7 @@ use std::hash::{BuildHasher, Hash};
 use std::iter::FusedIterator;
 use std::marker::PhantomData;
 
-use crate::{MapBase, MapExt, MapKind, Ref, Wrapper};
+use crate::{BiMap, MapBase, MapExt, MapKind, NaturalMap, Ref, TryReserveError, Wrapper};
 
 pub struct HashMapKind<S = std::collections::hash_map::RandomState> {
     marker: PhantomData<S>,
@@ -22,6 +22,24 @@ pub struct HashMap<K, V, S = hash_map::RandomState> {
     map: hash_map::HashMap<Ref<K>, Ref<V>, S>,
 }
 
+impl<K, V, S> HashMap<K, V, S> {
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            map: hash_map::HashMap::with_hasher(hasher),
+        }
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        Self {
+            map: hash_map::HashMap::with_capacity_and_hasher(capacity, hasher),
+        }
+    }
+
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+}
+
 impl<K, V, S> MapBase for HashMap<K, V, S>
 where
     K: Eq + Hash,
@@ -30,6 +48,8 @@ where
     type Key = K;
     type Val = V;
     type Iter<'a, K_: 'a, V_: 'a> = Iter<'a, K_, V_> where Self: 'a;
+    type IntoIter<K_, V_> = IntoIter<K_, V_>;
+    type Drain<'a, K_: 'a, V_: 'a> = Drain<'a, K_, V_> where Self: 'a;
 
     fn new() -> Self {
         Self {
@@ -37,6 +57,12 @@ where
         }
     }
 
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: hash_map::HashMap::with_capacity_and_hasher(capacity, S::default()),
+        }
+    }
+
     fn len(&self) -> usize {
         self.map.len()
     }
@@ -45,6 +71,26 @@ where
         self.map.is_empty()
     }
 
+    fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.map.try_reserve(additional)
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+    }
+
+    fn shrink_to(&mut self, min_capacity: usize) {
+        self.map.shrink_to(min_capacity);
+    }
+
     fn iter(&self) -> Self::Iter<'_, Self::Key, Self::Val> {
         Iter {
             iter: self.map.iter(),
@@ -54,6 +100,36 @@ where
     fn insert(&mut self, key: Ref<Self::Key>, val: Ref<Self::Val>) {
         self.map.insert(key, val);
     }
+
+    fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Self::Key, &Self::Val) -> bool,
+    {
+        self.map.retain(|k, v| f(k, v));
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    fn drain(&mut self) -> Self::Drain<'_, Self::Key, Self::Val> {
+        Drain {
+            iter: self.map.drain(),
+        }
+    }
+
+    fn into_iter(self) -> Self::IntoIter<Self::Key, Self::Val> {
+        IntoIter {
+            iter: self.map.into_iter(),
+        }
+    }
+}
+
+impl<K, V, S> NaturalMap for HashMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
 }
 
 impl<K, V, S, Q: ?Sized> MapExt<Q> for HashMap<K, V, S>
@@ -66,6 +142,10 @@ where
         self.map.get(Wrapper::wrap(key))
     }
 
+    fn get_key_value(&self, key: &Q) -> Option<(&Ref<Self::Key>, &Ref<Self::Val>)> {
+        self.map.get_key_value(Wrapper::wrap(key))
+    }
+
     fn contains(&self, key: &Q) -> bool {
         self.map.contains_key(Wrapper::wrap(key))
     }
@@ -75,10 +155,132 @@ where
     }
 }
 
+impl<L, R, S1, S2> BiMap<HashMap<L, R, S1>, HashMap<R, L, S2>> {
+    /// Creates an empty bimap whose left and right maps use the given
+    /// hashers.
+    pub fn with_hasher(left_hasher: S1, right_hasher: S2) -> Self {
+        BiMap {
+            lmap: HashMap::with_hasher(left_hasher),
+            rmap: HashMap::with_hasher(right_hasher),
+            auto_shrink: false,
+        }
+    }
+
+    /// Creates an empty bimap with at least the given capacity whose left and
+    /// right maps use the given hashers.
+    pub fn with_capacity_and_hasher(capacity: usize, left_hasher: S1, right_hasher: S2) -> Self {
+        BiMap {
+            lmap: HashMap::with_capacity_and_hasher(capacity, left_hasher),
+            rmap: HashMap::with_capacity_and_hasher(capacity, right_hasher),
+            auto_shrink: false,
+        }
+    }
+
+    /// Returns a reference to the left map's hasher.
+    pub fn left_hasher(&self) -> &S1 {
+        self.lmap.hasher()
+    }
+
+    /// Returns a reference to the right map's hasher.
+    pub fn right_hasher(&self) -> &S2 {
+        self.rmap.hasher()
+    }
+}
+
+/// Fails with the first repeated value found if the map isn't injective.
+impl<L, R, S, S1, S2> TryFrom<hash_map::HashMap<L, R, S>>
+    for BiMap<HashMap<L, R, S1>, HashMap<R, L, S2>>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    S1: BuildHasher + Default,
+    S2: BuildHasher + Default,
+{
+    type Error = R;
+
+    fn try_from(map: hash_map::HashMap<L, R, S>) -> Result<Self, R> {
+        let mut bimap = BiMap::with_capacity(map.len());
+        for (l, r) in map {
+            bimap.insert_no_overwrite(l, r).map_err(|(_, r)| r)?;
+        }
+        Ok(bimap)
+    }
+}
+
+/// Converts into a map from left to right values, which no longer keeps the
+/// right values unique. See [`BiMap::into_right_to_left_map`] for the other
+/// direction.
+impl<L, R, S, S1, S2> From<BiMap<HashMap<L, R, S1>, HashMap<R, L, S2>>>
+    for hash_map::HashMap<L, R, S>
+where
+    L: Eq + Hash + Clone,
+    R: Eq + Hash + Clone,
+    S: BuildHasher + Default,
+    S1: BuildHasher + Default,
+    S2: BuildHasher + Default,
+{
+    fn from(bimap: BiMap<HashMap<L, R, S1>, HashMap<R, L, S2>>) -> Self {
+        bimap.into_iter().collect()
+    }
+}
+
+impl<L, R, S1, S2> BiMap<HashMap<L, R, S1>, HashMap<R, L, S2>>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    S1: BuildHasher + Default,
+    S2: BuildHasher + Default,
+{
+    /// Converts into a map from right to left values, which no longer keeps
+    /// the left values unique.
+    pub fn into_right_to_left_map<S>(self) -> hash_map::HashMap<R, L, S>
+    where
+        L: Clone,
+        R: Clone,
+        S: BuildHasher + Default,
+    {
+        self.inverse().into_iter().collect()
+    }
+}
+
+impl<'a, L, R, S1, S2> Extend<(&'a L, &'a R)> for BiMap<HashMap<L, R, S1>, HashMap<R, L, S2>>
+where
+    L: Clone + Eq + Hash + 'a,
+    R: Clone + Eq + Hash + 'a,
+    S1: BuildHasher + Default,
+    S2: BuildHasher + Default,
+{
+    fn extend<I: IntoIterator<Item = (&'a L, &'a R)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(l, r)| (l.clone(), r.clone())));
+    }
+}
+
+impl<'a, L, R, S1, S2> FromIterator<(&'a L, &'a R)> for BiMap<HashMap<L, R, S1>, HashMap<R, L, S2>>
+where
+    L: Clone + Eq + Hash + 'a,
+    R: Clone + Eq + Hash + 'a,
+    S1: BuildHasher + Default,
+    S2: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (&'a L, &'a R)>>(iter: I) -> Self {
+        let mut bimap = BiMap::new();
+        bimap.extend(iter);
+        bimap
+    }
+}
+
 pub struct Iter<'a, K, V> {
     iter: hash_map::Iter<'a, Ref<K>, Ref<V>>,
 }
 
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Iter {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
 impl<'a, K, V> Iterator for Iter<'a, K, V> {
     type Item = (&'a Ref<K>, &'a Ref<V>);
 
@@ -94,3 +296,203 @@ impl<'a, K, V> Iterator for Iter<'a, K, V> {
 impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
 
 impl<'a, K, V> FusedIterator for Iter<'a, K, V> {}
+
+pub struct IntoIter<K, V> {
+    iter: hash_map::IntoIter<Ref<K>, Ref<V>>,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (Ref<K>, Ref<V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+
+pub struct Drain<'a, K, V> {
+    iter: hash_map::Drain<'a, Ref<K>, Ref<V>>,
+}
+
+impl<'a, K, V> Iterator for Drain<'a, K, V> {
+    type Item = (Ref<K>, Ref<V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Drain<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for Drain<'a, K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::BiHashMap;
+
+    #[test]
+    fn with_capacity_presizes_the_inner_map() {
+        let map: HashMap<u32, u32> = MapBase::with_capacity(100);
+        assert!(map.map.capacity() >= 100);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn deterministic_hashers_give_reproducible_iteration() {
+        type Fixed = std::hash::BuildHasherDefault<std::hash::DefaultHasher>;
+
+        let build = || {
+            let mut bimap: BiHashMap<u32, u32, Fixed, Fixed> =
+                BiMap::with_hasher(Fixed::default(), Fixed::default());
+            bimap.extend((0..100).map(|i| (i, 100 - i)));
+            bimap
+        };
+        let a = build();
+        let b = build();
+        assert!(a.iter().eq(b.iter()));
+        assert!(a.iter_inverse().eq(b.iter_inverse()));
+        let _: (&Fixed, &Fixed) = (a.left_hasher(), a.right_hasher());
+
+        let c: BiHashMap<u32, u32, Fixed, Fixed> =
+            BiMap::with_capacity_and_hasher(100, Fixed::default(), Fixed::default());
+        assert!(c.capacity() >= 100);
+    }
+
+    #[test]
+    fn try_from_accepts_injective_maps() {
+        let map: hash_map::HashMap<_, _> = [(1, 'a'), (2, 'b')].into_iter().collect();
+        let bimap: BiHashMap<_, _> = map.try_into().unwrap();
+        assert_eq!(bimap.get_right(&'b'), Some(&2));
+        assert!(bimap.is_valid());
+    }
+
+    #[test]
+    fn try_from_rejects_repeated_values() {
+        let map: hash_map::HashMap<_, _> = [(1, 'a'), (2, 'b'), (3, 'a')].into_iter().collect();
+        let result: Result<BiHashMap<_, _>, _> = map.try_into();
+        assert_eq!(result.err(), Some('a'));
+    }
+
+    #[test]
+    fn hashers_hash_like_the_inner_maps() {
+        use std::hash::{BuildHasherDefault, DefaultHasher, Hasher};
+
+        type Fixed = BuildHasherDefault<DefaultHasher>;
+
+        let bimap: BiHashMap<u32, char, Fixed, Fixed> = BiHashMap::default();
+        let mut hasher = DefaultHasher::new();
+        7u32.hash(&mut hasher);
+        assert_eq!(bimap.left_hasher().hash_one(7u32), hasher.finish());
+        let mut hasher = DefaultHasher::new();
+        'x'.hash(&mut hasher);
+        assert_eq!(bimap.right_hasher().hash_one('x'), hasher.finish());
+    }
+
+    #[test]
+    fn converts_into_plain_maps_in_both_directions() {
+        let bimap: BiHashMap<i32, char> = (0..26).map(|i| (i, (b'a' + i as u8) as char)).collect();
+        let map: hash_map::HashMap<i32, char> = bimap.clone().into();
+        assert_eq!(map.len(), bimap.len());
+        assert!(bimap.iter().all(|(l, r)| map.get(l) == Some(r)));
+
+        let map: hash_map::HashMap<char, i32> = bimap.clone().into_right_to_left_map();
+        assert_eq!(map.len(), bimap.len());
+        assert!(bimap.iter().all(|(l, r)| map.get(r) == Some(l)));
+    }
+
+    #[test]
+    fn default_extend_pairs_inserts_every_pair() {
+        let mut map: HashMap<i32, i32> = HashMap::new();
+        map.extend_pairs((0..100).map(|i| (Ref::new(i), Ref::new(-i))));
+        assert_eq!(map.len(), 100);
+        assert!((0..100).all(|i| map.get(&i).map(|r| **r) == Some(-i)));
+    }
+
+    #[test]
+    fn retain_keeps_exactly_the_matching_entries() {
+        let mut map: HashMap<i32, i32> = HashMap::new();
+        map.extend_pairs((0..100).map(|i| (Ref::new(i), Ref::new(-i))));
+        MapBase::retain(&mut map, |&k, &v| k % 3 == 0 && v > -50);
+        let mut keys: Vec<i32> = map.iter().map(|(k, _)| **k).collect();
+        keys.sort();
+        assert_eq!(keys, (0..50).step_by(3).collect::<Vec<_>>());
+        assert!(keys.iter().all(|k| map.get(k).map(|r| **r) == Some(-k)));
+    }
+
+    #[test]
+    fn with_capacity_and_hasher_applies_both() {
+        use std::hash::{DefaultHasher, Hasher};
+
+        #[derive(Default)]
+        struct Seeded(u64);
+
+        impl BuildHasher for Seeded {
+            type Hasher = DefaultHasher;
+
+            fn build_hasher(&self) -> DefaultHasher {
+                let mut hasher = DefaultHasher::new();
+                hasher.write_u64(self.0);
+                hasher
+            }
+        }
+
+        let build = || {
+            let mut bimap: BiHashMap<u32, u32, Seeded, Seeded> =
+                BiMap::with_capacity_and_hasher(500, Seeded(1), Seeded(2));
+            let capacities = bimap.capacities();
+            assert!(bimap.capacity() >= 500);
+            bimap.extend((0..500).map(|i| (i, 1000 - i)));
+            assert_eq!(bimap.capacities(), capacities);
+            bimap
+        };
+        let a = build();
+        assert_eq!((a.left_hasher().0, a.right_hasher().0), (1, 2));
+        assert!(a.iter().eq(build().iter()));
+        assert!(a.iter_inverse().eq(build().iter_inverse()));
+    }
+
+    #[test]
+    fn collects_borrowed_pairs_by_cloning() {
+        use std::string::{String, ToString};
+
+        let src: BiHashMap<u32, String> = (0..50).map(|i| (i, i.to_string())).collect();
+        let copy: BiHashMap<_, _> = src.iter().collect();
+        assert_eq!(copy, src);
+        assert!(copy.is_valid());
+
+        let mut extended: BiHashMap<u32, String> = BiHashMap::new();
+        extended.extend(src.iter());
+        assert_eq!(extended, src);
+    }
+
+    #[test]
+    fn clone_shares_nothing_with_the_original() {
+        let bimap: BiHashMap<i32, i32> = (0..100).map(|i| (i, -i)).collect();
+        let clone = bimap.clone();
+        assert!(clone.is_valid());
+        assert!(bimap.is_valid());
+        assert_eq!(clone, bimap);
+
+        let mut target: BiHashMap<i32, i32> = (0..5).map(|i| (i, i)).collect();
+        target.clone_from(&bimap);
+        assert!(target.is_valid());
+        assert_eq!(target, bimap);
+        let mut pairs: Vec<_> = target.into_iter().collect();
+        pairs.sort();
+        assert_eq!(pairs, (0..100).map(|i| (i, -i)).collect::<Vec<_>>());
+    }
+}
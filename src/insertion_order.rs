@@ -0,0 +1,372 @@
+use alloc::rc::Weak;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use core::ops::Deref;
+use core::{ptr, slice};
+
+use crate::{BiMap, Map, MapBase, MapExt, Overwritten, OverwrittenRefs, Ref};
+
+/// A `BiMap` that also remembers the order its pairs were inserted in, for
+/// reproducible iteration over a backend that has no order of its own.
+///
+/// Reads go through `Deref` to the inner bimap; only the methods below can
+/// modify it, so the log can't fall out of step.
+///
+/// The log holds a `Weak` to each left value rather than a copy of it, so
+/// values aren't cloned into it. A removed pair leaves a dead entry behind
+/// instead of being searched for, and dead entries are dropped in bulk once
+/// they outnumber the live ones. Each operation therefore costs the same as
+/// on a plain `BiMap` plus amortized O(1), and `iter_ordered` takes time
+/// proportional to the number of pairs.
+pub struct InsertionOrdered<LMap: MapBase, RMap> {
+    bimap: BiMap<LMap, RMap>,
+    log: Vec<Weak<LMap::Key>>,
+}
+
+/// An iterator over the pairs of an `InsertionOrdered` bimap, oldest first.
+pub struct InsertionOrderIter<'a, LMap: MapBase, RMap> {
+    bimap: &'a BiMap<LMap, RMap>,
+    log: slice::Iter<'a, Weak<LMap::Key>>,
+    remaining: usize,
+}
+
+impl<L, R, LMap, RMap> InsertionOrdered<LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    pub fn new() -> Self {
+        Self {
+            bimap: BiMap::new(),
+            log: Vec::new(),
+        }
+    }
+
+    /// Inserts the pair `(left, right)` as with [`BiMap::insert`]. The pair
+    /// becomes the newest one, unless it was already present, in which case
+    /// it keeps its place.
+    #[must_use = "use `insert_unchecked` if the overwritten pairs aren't needed"]
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        self.insert_logged(left, right).join()
+    }
+
+    /// Like `insert`, but drops any overwritten pairs instead of returning
+    /// them.
+    pub fn insert_unchecked(&mut self, left: L, right: R) {
+        drop(self.insert_logged(left, right));
+    }
+
+    fn insert_logged(&mut self, left: L, right: R) -> OverwrittenRefs<L, R> {
+        let present = match (
+            self.bimap.lmap.get_key_value(&left),
+            self.bimap.rmap.get(&right),
+        ) {
+            (Some((l0, _)), Some(l1)) => Ref::ptr_eq(l0, l1),
+            _ => false,
+        };
+        if present {
+            // The pair keeps its place in the log. Splitting the new values
+            // lets them be joined back without cloning.
+            return Overwritten::Pair(Ref::split(left), Ref::split(right));
+        }
+        let overwritten = self.bimap.remove_overlapping(&left, &right);
+        let weak = self.bimap.insert_raw_weak(left, right);
+        self.log.push(weak);
+        self.compact_log();
+        overwritten
+    }
+
+    /// Removes the pair containing `left`, as with [`BiMap::remove_left`].
+    pub fn remove_left<Q: ?Sized>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+        LMap: MapExt<Q>,
+    {
+        let removed = self.bimap.remove_left(left);
+        self.compact_log();
+        removed
+    }
+
+    /// Removes the pair containing `right`, as with
+    /// [`BiMap::remove_right`].
+    pub fn remove_right<Q: ?Sized>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+        RMap: MapExt<Q>,
+    {
+        let removed = self.bimap.remove_right(right);
+        self.compact_log();
+        removed
+    }
+
+    pub fn clear(&mut self) {
+        self.bimap.clear();
+        self.log.clear();
+    }
+
+    /// Returns an iterator over the pairs in the order they were inserted.
+    pub fn iter_ordered(&self) -> InsertionOrderIter<'_, LMap, RMap> {
+        InsertionOrderIter {
+            bimap: &self.bimap,
+            log: self.log.iter(),
+            remaining: self.bimap.len(),
+        }
+    }
+
+    /// Discards the log, returning the bimap.
+    pub fn into_inner(self) -> BiMap<LMap, RMap> {
+        self.bimap
+    }
+
+    fn compact_log(&mut self) {
+        if self.log.len() > 2 * self.bimap.len() {
+            let bimap = &self.bimap;
+            self.log.retain(|weak| live_pair(bimap, weak).is_some());
+        }
+    }
+}
+
+// Looks up the pair a log entry was made for. A clone of the left value's
+// `Ref` can keep it alive after the pair is removed, so the entry only counts
+// while the bimap still holds that same value.
+fn live_pair<'a, L, R, LMap, RMap>(
+    bimap: &'a BiMap<LMap, RMap>,
+    weak: &Weak<L>,
+) -> Option<(&'a L, &'a R)>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    let left = weak.upgrade()?;
+    let (l, r) = bimap.get_left_full(&*left)?;
+    ptr::eq(l, &*left).then_some((l, r))
+}
+
+impl<L, R, LMap, RMap> Default for InsertionOrdered<LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Starts the log from the bimap's own iteration order.
+impl<L, R, LMap, RMap> From<BiMap<LMap, RMap>> for InsertionOrdered<LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    fn from(bimap: BiMap<LMap, RMap>) -> Self {
+        let log = bimap.lmap.iter().map(|(l, _)| Ref::downgrade(l)).collect();
+        Self { bimap, log }
+    }
+}
+
+impl<LMap: MapBase, RMap> Deref for InsertionOrdered<LMap, RMap> {
+    type Target = BiMap<LMap, RMap>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.bimap
+    }
+}
+
+impl<'a, L, R, LMap, RMap> Iterator for InsertionOrderIter<'a, LMap, RMap>
+where
+    L: 'a,
+    R: 'a,
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bimap = self.bimap;
+        let pair = self.log.find_map(|weak| live_pair(bimap, weak))?;
+        self.remaining -= 1;
+        Some(pair)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, L, R, LMap, RMap> DoubleEndedIterator for InsertionOrderIter<'a, LMap, RMap>
+where
+    L: 'a,
+    R: 'a,
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let bimap = self.bimap;
+        let pair = self
+            .log
+            .by_ref()
+            .rev()
+            .find_map(|weak| live_pair(bimap, weak))?;
+        self.remaining -= 1;
+        Some(pair)
+    }
+}
+
+impl<'a, L, R, LMap, RMap> ExactSizeIterator for InsertionOrderIter<'a, LMap, RMap>
+where
+    L: 'a,
+    R: 'a,
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+}
+
+impl<'a, L, R, LMap, RMap> FusedIterator for InsertionOrderIter<'a, LMap, RMap>
+where
+    L: 'a,
+    R: 'a,
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::BiBTreeMap;
+
+    fn ordered<L: Ord, R: Ord>() -> InsertionOrdered<
+        <crate::BTreeMapKind as crate::MapKind<L, R>>::Map,
+        <crate::BTreeMapKind as crate::MapKind<R, L>>::Map,
+    > {
+        BiBTreeMap::new().into()
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn iter_ordered_survives_rehashes() {
+        let mut bimap: InsertionOrdered<_, _> = crate::BiHashMap::<u32, u32>::new().into();
+        // Multiplying by an odd constant scrambles the values without
+        // repeating any of them.
+        let pairs: Vec<(u32, u32)> = (0..1000u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761), i))
+            .collect();
+        let mut rehashes = 0;
+        for &(l, r) in &pairs {
+            let capacity = bimap.capacity();
+            bimap.insert_unchecked(l, r);
+            if bimap.capacity() != capacity {
+                rehashes += 1;
+            }
+        }
+        assert!(rehashes > 1);
+        let expected: Vec<_> = pairs.iter().map(|(l, r)| (l, r)).collect();
+        assert_eq!(bimap.iter_ordered().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn removals_and_overwrites_update_the_order() {
+        let mut bimap = ordered();
+        for (l, r) in [(3, 'c'), (1, 'a'), (2, 'b'), (4, 'd')] {
+            bimap.insert_unchecked(l, r);
+        }
+        assert_eq!(bimap.remove_left(&1), Some((1, 'a')));
+        assert_eq!(bimap.remove_right(&'d'), Some((4, 'd')));
+        assert_eq!(bimap.insert(3, 'c'), Overwritten::Pair(3, 'c'));
+        assert_eq!(bimap.insert(3, 'x'), Overwritten::Left(3, 'c'));
+        bimap.insert_unchecked(5, 'e');
+        assert_eq!(
+            bimap.iter_ordered().collect::<Vec<_>>(),
+            vec![(&2, &'b'), (&3, &'x'), (&5, &'e')]
+        );
+        assert_eq!(
+            bimap.iter_ordered().rev().collect::<Vec<_>>(),
+            vec![(&5, &'e'), (&3, &'x'), (&2, &'b')]
+        );
+        assert_eq!(bimap.iter_ordered().len(), 3);
+        assert!(bimap.is_valid());
+    }
+
+    #[test]
+    fn dead_entries_are_compacted() {
+        let mut bimap = ordered();
+        for i in 0..1000 {
+            bimap.insert_unchecked(i % 10, i);
+            assert!(bimap.log.len() <= 2 * bimap.len() + 1);
+        }
+        let lefts: Vec<_> = bimap.iter_ordered().map(|(&l, _)| l).collect();
+        assert_eq!(lefts, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn values_are_not_cloned() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+        struct Counted(u8);
+
+        impl Clone for Counted {
+            fn clone(&self) -> Self {
+                CLONES.fetch_add(1, Ordering::Relaxed);
+                Counted(self.0)
+            }
+        }
+
+        let mut bimap = ordered();
+        bimap.insert_unchecked(Counted(2), Counted(0));
+        assert_eq!(bimap.insert(Counted(1), Counted(1)), Overwritten::Neither);
+        assert_eq!(
+            bimap.insert(Counted(1), Counted(1)),
+            Overwritten::Pair(Counted(1), Counted(1))
+        );
+        assert_eq!(
+            bimap.remove_left(&Counted(2)),
+            Some((Counted(2), Counted(0)))
+        );
+        assert_eq!(bimap.iter_ordered().count(), 1);
+        assert_eq!(CLONES.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn removal_keeps_the_log_bounded() {
+        let mut bimap = ordered();
+        for i in 0..100 {
+            bimap.insert_unchecked(i, i);
+        }
+        for i in 0..90 {
+            assert_eq!(bimap.remove_left(&i), Some((i, i)));
+            assert!(bimap.log.len() <= 2 * bimap.len() + 1);
+        }
+        for i in 90..100 {
+            assert_eq!(bimap.remove_right(&i), Some((i, i)));
+        }
+        assert!(bimap.log.is_empty());
+    }
+
+    #[test]
+    fn outstanding_refs_dont_revive_removed_pairs() {
+        let mut bimap = ordered();
+        bimap.insert_unchecked(1, 'a');
+        bimap.insert_unchecked(2, 'b');
+        let held = bimap.clone_left_ref(&1).unwrap();
+        assert_eq!(bimap.remove_left(&1), Some((1, 'a')));
+        assert_eq!(bimap.iter_ordered().collect::<Vec<_>>(), [(&2, &'b')]);
+        bimap.insert_unchecked(1, 'c');
+        assert_eq!(
+            bimap.iter_ordered().collect::<Vec<_>>(),
+            [(&2, &'b'), (&1, &'c')]
+        );
+        assert_eq!(*held, 1);
+    }
+}
@@ -0,0 +1,698 @@
+use alloc::vec;
+use core::iter::{Cloned, FusedIterator};
+use core::ops::RangeBounds;
+
+use crate::{BiMap, Map, MapBase, OrderedMapBase, OrderedMapExt, Ref};
+
+impl<LMap: MapBase, RMap> BiMap<LMap, RMap> {
+    pub fn iter(&self) -> Iter<'_, LMap> {
+        Iter {
+            iter: self.lmap.iter(),
+        }
+    }
+
+    /// Returns an iterator over the `Ref`s stored in the left map, which can be
+    /// cloned to share the values without copying them.
+    ///
+    /// Removing a pair while a clone of one of its `Ref`s is still alive
+    /// clones the value, since it can't be moved out of the bimap.
+    pub fn iter_refs(&self) -> LMap::Iter<'_, LMap::Key, LMap::Val> {
+        self.lmap.iter()
+    }
+
+    /// Returns an iterator over the pairs with the roles of the left and right
+    /// values swapped, as if iterating over the inverse bimap.
+    pub fn iter_inverse(&self) -> Iter<'_, RMap>
+    where
+        RMap: MapBase,
+    {
+        Iter {
+            iter: self.rmap.iter(),
+        }
+    }
+
+    /// Returns the same pairs as `iter`, which play the role of a map's
+    /// entries.
+    pub fn pairs(&self) -> Pairs<'_, LMap> {
+        Pairs { iter: self.iter() }
+    }
+
+    /// Returns an iterator over the left values, the counterpart of
+    /// `HashMap::keys`.
+    pub fn left_values(&self) -> LeftValues<'_, LMap> {
+        LeftValues {
+            iter: self.lmap.iter(),
+        }
+    }
+
+    /// Returns an iterator over the right values, the counterpart of
+    /// `HashMap::values`.
+    pub fn right_values(&self) -> RightValues<'_, LMap> {
+        RightValues {
+            iter: self.lmap.iter(),
+        }
+    }
+
+    /// An alias for `left_values`, for code written against std's maps.
+    pub fn left_keys(&self) -> LeftValues<'_, LMap> {
+        self.left_values()
+    }
+
+    /// Returns an iterator over clones of the left values.
+    pub fn cloned_left_values(&self) -> Cloned<LeftValues<'_, LMap>>
+    where
+        LMap::Key: Clone,
+    {
+        self.left_values().cloned()
+    }
+
+    /// Returns an iterator over clones of the right values.
+    pub fn cloned_right_values(&self) -> Cloned<RightValues<'_, LMap>>
+    where
+        LMap::Val: Clone,
+    {
+        self.right_values().cloned()
+    }
+
+    pub fn into_left_values(self) -> IntoLeftValues<LMap>
+    where
+        LMap::Key: Clone,
+        LMap::Val: Clone,
+    {
+        IntoLeftValues {
+            iter: self.into_iter(),
+        }
+    }
+
+    pub fn into_right_values(self) -> IntoRightValues<LMap>
+    where
+        LMap::Key: Clone,
+        LMap::Val: Clone,
+    {
+        IntoRightValues {
+            iter: self.into_iter(),
+        }
+    }
+
+    /// Removes every pair from the bimap, returning them in an iterator. The
+    /// bimap keeps its allocated capacity, unless auto-shrinking is enabled,
+    /// in which case the pairs are moved into a buffer up front so that the
+    /// maps can be shrunk right away.
+    pub fn drain(&mut self) -> Drain<'_, LMap>
+    where
+        RMap: MapBase,
+    {
+        // As in `into_iter`, clearing the right map first leaves the left map
+        // holding the only `Rc` to each value.
+        self.rmap.clear();
+        let iter = if self.auto_shrink {
+            let pairs: vec::Vec<_> = self.lmap.drain().collect();
+            self.maybe_shrink();
+            DrainInner::Buffered(pairs.into_iter())
+        } else {
+            DrainInner::Lazy(self.lmap.drain())
+        };
+        Drain { iter }
+    }
+
+    /// Returns an iterator over the pairs whose left values lie in `range`,
+    /// in ascending order of left value.
+    pub fn range_left<Q: ?Sized, T>(&self, range: T) -> LeftRange<'_, LMap>
+    where
+        LMap: OrderedMapExt<Q>,
+        T: RangeBounds<Q>,
+    {
+        LeftRange {
+            iter: self.lmap.range(range),
+        }
+    }
+
+    /// Returns an iterator over the pairs whose right values lie in `range`,
+    /// in ascending order of right value.
+    pub fn range_right<Q: ?Sized, T>(&self, range: T) -> RightRange<'_, RMap>
+    where
+        RMap: OrderedMapExt<Q>,
+        T: RangeBounds<Q>,
+    {
+        RightRange {
+            iter: self.rmap.range(range),
+        }
+    }
+}
+
+/// An iterator over the pairs of a `BiMap`.
+pub struct Iter<'a, LMap: MapBase + 'a> {
+    pub(crate) iter: LMap::Iter<'a, LMap::Key, LMap::Val>,
+}
+
+impl<'a, LMap: MapBase> Clone for Iter<'a, LMap>
+where
+    LMap::Iter<'a, LMap::Key, LMap::Val>: Clone,
+{
+    fn clone(&self) -> Self {
+        Iter {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, LMap: MapBase, RMap> IntoIterator for &'a BiMap<LMap, RMap> {
+    type Item = (&'a LMap::Key, &'a LMap::Val);
+    type IntoIter = Iter<'a, LMap>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, LMap: MapBase> Iterator for Iter<'a, LMap> {
+    type Item = (&'a LMap::Key, &'a LMap::Val);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (l, r) = self.iter.next()?;
+        Some((&**l, &**r))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, LMap: MapBase> DoubleEndedIterator for Iter<'a, LMap>
+where
+    LMap::Iter<'a, LMap::Key, LMap::Val>: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (l, r) = self.iter.next_back()?;
+        Some((&**l, &**r))
+    }
+}
+
+impl<LMap: MapBase> ExactSizeIterator for Iter<'_, LMap> {}
+
+impl<LMap: MapBase> FusedIterator for Iter<'_, LMap> {}
+
+/// An iterator over the pairs of a `BiMap`, returned by [`BiMap::pairs`].
+pub struct Pairs<'a, LMap: MapBase + 'a> {
+    iter: Iter<'a, LMap>,
+}
+
+impl<'a, LMap: MapBase> Clone for Pairs<'a, LMap>
+where
+    LMap::Iter<'a, LMap::Key, LMap::Val>: Clone,
+{
+    fn clone(&self) -> Self {
+        Pairs {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, LMap: MapBase> Iterator for Pairs<'a, LMap> {
+    type Item = (&'a LMap::Key, &'a LMap::Val);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, LMap: MapBase> DoubleEndedIterator for Pairs<'a, LMap>
+where
+    LMap::Iter<'a, LMap::Key, LMap::Val>: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<LMap: MapBase> ExactSizeIterator for Pairs<'_, LMap> {}
+
+impl<LMap: MapBase> FusedIterator for Pairs<'_, LMap> {}
+
+/// An iterator over the left values of a `BiMap`.
+pub struct LeftValues<'a, LMap: MapBase + 'a> {
+    iter: LMap::Iter<'a, LMap::Key, LMap::Val>,
+}
+
+impl<'a, LMap: MapBase> Clone for LeftValues<'a, LMap>
+where
+    LMap::Iter<'a, LMap::Key, LMap::Val>: Clone,
+{
+    fn clone(&self) -> Self {
+        LeftValues {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, LMap: MapBase> Iterator for LeftValues<'a, LMap> {
+    type Item = &'a LMap::Key;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (l, _) = self.iter.next()?;
+        Some(&**l)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, LMap: MapBase> DoubleEndedIterator for LeftValues<'a, LMap>
+where
+    LMap::Iter<'a, LMap::Key, LMap::Val>: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (l, _) = self.iter.next_back()?;
+        Some(&**l)
+    }
+}
+
+impl<LMap: MapBase> ExactSizeIterator for LeftValues<'_, LMap> {}
+
+impl<LMap: MapBase> FusedIterator for LeftValues<'_, LMap> {}
+
+/// An iterator over the right values of a `BiMap`.
+pub struct RightValues<'a, LMap: MapBase + 'a> {
+    iter: LMap::Iter<'a, LMap::Key, LMap::Val>,
+}
+
+impl<'a, LMap: MapBase> Clone for RightValues<'a, LMap>
+where
+    LMap::Iter<'a, LMap::Key, LMap::Val>: Clone,
+{
+    fn clone(&self) -> Self {
+        RightValues {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, LMap: MapBase> Iterator for RightValues<'a, LMap> {
+    type Item = &'a LMap::Val;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, r) = self.iter.next()?;
+        Some(&**r)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, LMap: MapBase> DoubleEndedIterator for RightValues<'a, LMap>
+where
+    LMap::Iter<'a, LMap::Key, LMap::Val>: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (_, r) = self.iter.next_back()?;
+        Some(&**r)
+    }
+}
+
+impl<LMap: MapBase> ExactSizeIterator for RightValues<'_, LMap> {}
+
+impl<LMap: MapBase> FusedIterator for RightValues<'_, LMap> {}
+
+/// An iterator over a range of left values of a `BiMap`.
+pub struct LeftRange<'a, LMap: OrderedMapBase + 'a> {
+    iter: LMap::Range<'a, LMap::Key, LMap::Val>,
+}
+
+impl<'a, LMap: OrderedMapBase> Clone for LeftRange<'a, LMap>
+where
+    LMap::Range<'a, LMap::Key, LMap::Val>: Clone,
+{
+    fn clone(&self) -> Self {
+        LeftRange {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, LMap: OrderedMapBase> Iterator for LeftRange<'a, LMap> {
+    type Item = (&'a LMap::Key, &'a LMap::Val);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (l, r) = self.iter.next()?;
+        Some((&**l, &**r))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<LMap: OrderedMapBase> DoubleEndedIterator for LeftRange<'_, LMap> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (l, r) = self.iter.next_back()?;
+        Some((&**l, &**r))
+    }
+}
+
+impl<LMap: OrderedMapBase> FusedIterator for LeftRange<'_, LMap> {}
+
+/// An iterator over a range of right values of a `BiMap`.
+pub struct RightRange<'a, RMap: OrderedMapBase + 'a> {
+    iter: RMap::Range<'a, RMap::Key, RMap::Val>,
+}
+
+impl<'a, RMap: OrderedMapBase> Clone for RightRange<'a, RMap>
+where
+    RMap::Range<'a, RMap::Key, RMap::Val>: Clone,
+{
+    fn clone(&self) -> Self {
+        RightRange {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, RMap: OrderedMapBase> Iterator for RightRange<'a, RMap> {
+    type Item = (&'a RMap::Val, &'a RMap::Key);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (r, l) = self.iter.next()?;
+        Some((&**l, &**r))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<RMap: OrderedMapBase> DoubleEndedIterator for RightRange<'_, RMap> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (r, l) = self.iter.next_back()?;
+        Some((&**l, &**r))
+    }
+}
+
+impl<RMap: OrderedMapBase> FusedIterator for RightRange<'_, RMap> {}
+
+/// An owning iterator over the pairs of a `BiMap`.
+pub struct IntoIter<LMap: MapBase> {
+    iter: LMap::IntoIter<LMap::Key, LMap::Val>,
+}
+
+impl<LMap: MapBase, RMap> IntoIterator for BiMap<LMap, RMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+{
+    type Item = (LMap::Key, LMap::Val);
+    type IntoIter = IntoIter<LMap>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Dropping the right map first leaves the left map holding the only
+        // `Rc` to each value.
+        drop(self.rmap);
+        IntoIter {
+            iter: self.lmap.into_iter(),
+        }
+    }
+}
+
+impl<LMap: MapBase> Iterator for IntoIter<LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+{
+    type Item = (LMap::Key, LMap::Val);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (l, r) = self.iter.next()?;
+        Some((Ref::into_inner(l), Ref::into_inner(r)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<LMap: MapBase> DoubleEndedIterator for IntoIter<LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+    LMap::IntoIter<LMap::Key, LMap::Val>: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (l, r) = self.iter.next_back()?;
+        Some((Ref::into_inner(l), Ref::into_inner(r)))
+    }
+}
+
+impl<LMap: MapBase> ExactSizeIterator for IntoIter<LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+{
+}
+
+impl<LMap: MapBase> FusedIterator for IntoIter<LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+{
+}
+
+/// An owning iterator over the left values of a `BiMap`.
+pub struct IntoLeftValues<LMap: MapBase> {
+    iter: IntoIter<LMap>,
+}
+
+impl<LMap: MapBase> Iterator for IntoLeftValues<LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+{
+    type Item = LMap::Key;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (l, _) = self.iter.next()?;
+        Some(l)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<LMap: MapBase> DoubleEndedIterator for IntoLeftValues<LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+    LMap::IntoIter<LMap::Key, LMap::Val>: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (l, _) = self.iter.next_back()?;
+        Some(l)
+    }
+}
+
+impl<LMap: MapBase> ExactSizeIterator for IntoLeftValues<LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+{
+}
+
+impl<LMap: MapBase> FusedIterator for IntoLeftValues<LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+{
+}
+
+/// An owning iterator over the right values of a `BiMap`.
+pub struct IntoRightValues<LMap: MapBase> {
+    iter: IntoIter<LMap>,
+}
+
+impl<LMap: MapBase> Iterator for IntoRightValues<LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+{
+    type Item = LMap::Val;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, r) = self.iter.next()?;
+        Some(r)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<LMap: MapBase> DoubleEndedIterator for IntoRightValues<LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+    LMap::IntoIter<LMap::Key, LMap::Val>: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (_, r) = self.iter.next_back()?;
+        Some(r)
+    }
+}
+
+impl<LMap: MapBase> ExactSizeIterator for IntoRightValues<LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+{
+}
+
+impl<LMap: MapBase> FusedIterator for IntoRightValues<LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+{
+}
+
+/// A draining iterator over the pairs of a `BiMap`.
+pub struct Drain<'a, LMap: MapBase + 'a> {
+    iter: DrainInner<'a, LMap>,
+}
+
+enum DrainInner<'a, LMap: MapBase + 'a> {
+    Lazy(LMap::Drain<'a, LMap::Key, LMap::Val>),
+    Buffered(vec::IntoIter<(Ref<LMap::Key>, Ref<LMap::Val>)>),
+}
+
+impl<LMap: MapBase> Iterator for DrainInner<'_, LMap> {
+    type Item = (Ref<LMap::Key>, Ref<LMap::Val>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            DrainInner::Lazy(iter) => iter.next(),
+            DrainInner::Buffered(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            DrainInner::Lazy(iter) => iter.size_hint(),
+            DrainInner::Buffered(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<'a, LMap: MapBase> DoubleEndedIterator for DrainInner<'a, LMap>
+where
+    LMap::Drain<'a, LMap::Key, LMap::Val>: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            DrainInner::Lazy(iter) => iter.next_back(),
+            DrainInner::Buffered(iter) => iter.next_back(),
+        }
+    }
+}
+
+impl<LMap: MapBase> Iterator for Drain<'_, LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+{
+    type Item = (LMap::Key, LMap::Val);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (l, r) = self.iter.next()?;
+        Some((Ref::into_inner(l), Ref::into_inner(r)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, LMap: MapBase> DoubleEndedIterator for Drain<'a, LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+    LMap::Drain<'a, LMap::Key, LMap::Val>: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (l, r) = self.iter.next_back()?;
+        Some((Ref::into_inner(l), Ref::into_inner(r)))
+    }
+}
+
+impl<LMap: MapBase> ExactSizeIterator for Drain<'_, LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+{
+}
+
+impl<LMap: MapBase> FusedIterator for Drain<'_, LMap>
+where
+    LMap::Key: Clone,
+    LMap::Val: Clone,
+{
+}
+
+/// An iterator that removes and yields the pairs of a `BiMap` matching a
+/// predicate. Pairs not yet visited when it is dropped are left in place.
+pub struct ExtractIf<'a, LMap: MapBase, RMap: MapBase, F> {
+    pub(crate) bimap: &'a mut BiMap<LMap, RMap>,
+    pub(crate) lefts: vec::IntoIter<Ref<LMap::Key>>,
+    pub(crate) pred: F,
+}
+
+impl<L, R, LMap, RMap, F> Iterator for ExtractIf<'_, LMap, RMap, F>
+where
+    L: Clone,
+    R: Clone,
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+    F: FnMut(&L, &R) -> bool,
+{
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for left in self.lefts.by_ref() {
+            let right = self.bimap.lmap.get(&left).expect("bimap invariant");
+            if !(self.pred)(&left, right) {
+                continue;
+            }
+            let (l0, r0) = self.bimap.lmap.remove(&left).expect("bimap invariant");
+            let (r1, l1) = self.bimap.rmap.remove(&r0).expect("bimap invariant");
+            // Release the snapshot's `Rc` so that each value is shared by
+            // exactly two again.
+            drop(left);
+            return Some((Ref::join(l0, l1), Ref::join(r0, r1)));
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.lefts.len()))
+    }
+}
+
+impl<L, R, LMap, RMap, F> FusedIterator for ExtractIf<'_, LMap, RMap, F>
+where
+    L: Clone,
+    R: Clone,
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+    F: FnMut(&L, &R) -> bool,
+{
+}
+
+impl<LMap: MapBase, RMap: MapBase, F> Drop for ExtractIf<'_, LMap, RMap, F> {
+    fn drop(&mut self) {
+        // Shrink once at the end, rather than repeatedly as pairs are
+        // extracted.
+        self.bimap.maybe_shrink();
+    }
+}
@@ -1,25 +1,127 @@
+#![no_std]
+#![allow(clippy::type_complexity)]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod btree_map;
+#[cfg(feature = "std")]
+mod builder;
+mod entry;
+// There is no hash backend without `std`: `alloc` has no hash table to build
+// one on, nor a source of random seeds for a `BuildHasher`.
+#[cfg(feature = "std")]
 mod hash_map;
+mod insertion_order;
+mod iter;
+mod sorted_vec_map;
+#[cfg(test)]
+mod tests;
+mod view;
 
-use std::borrow::Borrow;
-use std::hash::Hash;
-use std::ops::Deref;
-use std::rc::Rc;
+pub use alloc::collections::TryReserveError;
+use alloc::rc::{Rc, Weak};
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FusedIterator;
+use core::mem;
+use core::ops::{Bound, Deref, Index, RangeBounds};
+#[cfg(feature = "std")]
+use std::collections::hash_map::RandomState;
 
-use hash_map::HashMapKind;
+pub use btree_map::BTreeMapKind;
+#[cfg(feature = "std")]
+pub use builder::{BiMapBuilder, OrderedBiMapBuilder};
+pub use entry::{
+    LeftEntry, OccupiedLeftEntry, OccupiedRightEntry, RightEntry, VacantLeftEntry, VacantRightEntry,
+};
+#[cfg(feature = "std")]
+pub use hash_map::HashMapKind;
+pub use insertion_order::{InsertionOrderIter, InsertionOrdered};
+pub use iter::{
+    Drain, ExtractIf, IntoIter, IntoLeftValues, IntoRightValues, Iter, LeftRange, LeftValues,
+    Pairs, RightRange, RightValues,
+};
+pub use sorted_vec_map::{Comparator, NaturalOrder, SortedVecMapKind};
+pub use view::MapView;
 
 /// A reference to a value in a `BiMap`.
+///
+/// A clone of a `Ref` keeps its value alive after the pair is removed from
+/// the bimap. The value can then no longer be moved out, so methods that
+/// return removed values clone them instead, and require `L: Clone` and
+/// `R: Clone`. [`BiMap::remove_left_shared`] returns the `Ref`s themselves.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Ref<T> {
     ptr: Rc<T>,
 }
 
 impl<T> Ref<T> {
-    fn join(x: Ref<T>, y: Ref<T>) -> T {
-        // Ensures that x and y are the only two `Rc`s pointing to the
-        // allocated value.
-        assert!(Rc::ptr_eq(&x.ptr, &y.ptr) && Rc::strong_count(&x.ptr) == 2);
+    /// Creates a `Ref` to `value`. A pair of maps handed to
+    /// [`BiMap::from_parts`] must hold exactly two `Ref`s to each value, one
+    /// made here and the other cloned from it.
+    pub fn new(value: T) -> Ref<T> {
+        Ref {
+            ptr: Rc::new(value),
+        }
+    }
+
+    fn split(value: T) -> (Ref<T>, Ref<T>) {
+        let x = Ref::new(value);
+        let y = x.clone();
+        (x, y)
+    }
+
+    fn join(x: Ref<T>, y: Ref<T>) -> T
+    where
+        T: Clone,
+    {
+        debug_assert!(Rc::ptr_eq(&x.ptr, &y.ptr));
         drop(x);
-        Rc::try_unwrap(y.ptr).ok().unwrap()
+        Ref::into_inner(y)
+    }
+
+    fn ptr_eq(x: &Ref<T>, y: &Ref<T>) -> bool {
+        Rc::ptr_eq(&x.ptr, &y.ptr)
+    }
+
+    fn strong_count(x: &Ref<T>) -> usize {
+        Rc::strong_count(&x.ptr)
+    }
+
+    fn downgrade(x: &Ref<T>) -> Weak<T> {
+        Rc::downgrade(&x.ptr)
+    }
+
+    /// Returns the inner value if `x` is the only `Ref` pointing to it,
+    /// otherwise returns `x` unchanged.
+    ///
+    /// Each value in a `BiMap` is pointed to by exactly two `Ref`s, one in
+    /// each inner map, so a value removed from a single map can only be
+    /// recovered once the other `Ref` has been dropped.
+    pub fn try_into_inner(x: Ref<T>) -> Result<T, Ref<T>> {
+        Rc::try_unwrap(x.ptr).map_err(|ptr| Ref { ptr })
+    }
+
+    fn into_inner(x: Ref<T>) -> T
+    where
+        T: Clone,
+    {
+        // A clone handed out by the bimap may still be alive, in which case
+        // it keeps the shared value and a copy is returned instead.
+        Rc::try_unwrap(x.ptr).unwrap_or_else(|ptr| T::clone(&ptr))
+    }
+}
+
+impl<T> Clone for Ref<T> {
+    fn clone(&self) -> Self {
+        Ref {
+            ptr: Rc::clone(&self.ptr),
+        }
     }
 }
 
@@ -58,18 +160,47 @@ pub trait MapBase {
     type Key;
     type Val;
     type Iter<'a, K: 'a, V: 'a>: Iterator<Item = (&'a Ref<K>, &'a Ref<V>)>
+        + ExactSizeIterator
+        + FusedIterator
+    where
+        Self: 'a;
+    type IntoIter<K, V>: Iterator<Item = (Ref<K>, Ref<V>)> + ExactSizeIterator + FusedIterator;
+    type Drain<'a, K: 'a, V: 'a>: Iterator<Item = (Ref<K>, Ref<V>)>
+        + ExactSizeIterator
+        + FusedIterator
     where
         Self: 'a;
 
     fn new() -> Self;
+    fn with_capacity(capacity: usize) -> Self;
     fn len(&self) -> usize;
     fn is_empty(&self) -> bool;
+    fn capacity(&self) -> usize;
+    fn reserve(&mut self, additional: usize);
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError>;
+    fn shrink_to_fit(&mut self);
+    fn shrink_to(&mut self, min_capacity: usize);
     fn iter(&self) -> Self::Iter<'_, Self::Key, Self::Val>;
     fn insert(&mut self, key: Ref<Self::Key>, val: Ref<Self::Val>);
+    fn extend_pairs<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (Ref<Self::Key>, Ref<Self::Val>)>,
+    {
+        for (key, val) in iter {
+            self.insert(key, val);
+        }
+    }
+    fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&Self::Key, &Self::Val) -> bool;
+    fn clear(&mut self);
+    fn drain(&mut self) -> Self::Drain<'_, Self::Key, Self::Val>;
+    fn into_iter(self) -> Self::IntoIter<Self::Key, Self::Val>;
 }
 
 pub trait MapExt<Q: ?Sized = <Self as MapBase>::Key>: MapBase {
     fn get(&self, key: &Q) -> Option<&Ref<Self::Val>>;
+    fn get_key_value(&self, key: &Q) -> Option<(&Ref<Self::Key>, &Ref<Self::Val>)>;
     fn contains(&self, key: &Q) -> bool;
     fn remove(&mut self, key: &Q) -> Option<(Ref<Self::Key>, Ref<Self::Val>)>;
 }
@@ -78,9 +209,160 @@ pub trait Map: MapBase + MapExt {}
 
 impl<T> Map for T where T: MapBase + MapExt {}
 
+/// The pairs removed from a `BiMap` by an insertion.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Overwritten<L, R> {
+    /// No pairs were removed.
+    Neither,
+    /// The left value was already present; its former pair was removed.
+    Left(L, R),
+    /// The right value was already present; its former pair was removed.
+    Right(L, R),
+    /// The exact pair being inserted was already present.
+    Pair(L, R),
+    /// Both values were present in two distinct pairs, given as the pair
+    /// containing the left value followed by the pair containing the right.
+    Both((L, R), (L, R)),
+}
+
+// The pairs removed by an insertion, as the two `Ref`s to each value.
+type OverwrittenRefs<L, R> = Overwritten<(Ref<L>, Ref<L>), (Ref<R>, Ref<R>)>;
+
+impl<L, R> OverwrittenRefs<L, R> {
+    fn join(self) -> Overwritten<L, R>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        let join = |(l0, l1), (r0, r1)| (Ref::join(l0, l1), Ref::join(r0, r1));
+        match self {
+            Overwritten::Neither => Overwritten::Neither,
+            Overwritten::Left(l, r) => {
+                let (l, r) = join(l, r);
+                Overwritten::Left(l, r)
+            }
+            Overwritten::Right(l, r) => {
+                let (l, r) = join(l, r);
+                Overwritten::Right(l, r)
+            }
+            Overwritten::Pair(l, r) => {
+                let (l, r) = join(l, r);
+                Overwritten::Pair(l, r)
+            }
+            Overwritten::Both((l0, r0), (l1, r1)) => Overwritten::Both(join(l0, r0), join(l1, r1)),
+        }
+    }
+}
+
+/// A value found on one side of a `BiMap` or the other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Either<L, R> {
+    /// A left value.
+    Left(L),
+    /// A right value.
+    Right(R),
+}
+
+/// The error returned by `BiMap::try_get_left` and `BiMap::try_get_right`
+/// when the value isn't present.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyNotFound;
+
+impl fmt::Display for KeyNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("value not in bimap")
+    }
+}
+
+impl core::error::Error for KeyNotFound {}
+
+/// The error returned by `BiMap::replace_right`, which hands back the new
+/// right value.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ReplaceRightError<R> {
+    /// The left value isn't present.
+    LeftNotFound(R),
+    /// The new right value is already paired with a different left value.
+    RightCollision(R),
+}
+
+impl<R> ReplaceRightError<R> {
+    /// Returns the right value that couldn't be inserted.
+    pub fn into_inner(self) -> R {
+        match self {
+            ReplaceRightError::LeftNotFound(right) | ReplaceRightError::RightCollision(right) => {
+                right
+            }
+        }
+    }
+}
+
+impl<R> fmt::Display for ReplaceRightError<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplaceRightError::LeftNotFound(_) => f.write_str("left value not in bimap"),
+            ReplaceRightError::RightCollision(_) => {
+                f.write_str("right value already paired with a different left value")
+            }
+        }
+    }
+}
+
+impl<R: fmt::Debug> core::error::Error for ReplaceRightError<R> {}
+
+/// A left value used to index a `BiMap`, as in `bimap[Left(&l)]`.
+pub struct Left<'a, Q: ?Sized>(pub &'a Q);
+
+/// A right value used to index a `BiMap`, as in `bimap[Right(&r)]`.
+pub struct Right<'a, Q: ?Sized>(pub &'a Q);
+
+/// A map whose `iter` and `retain` visit entries in ascending key order.
+pub trait OrderedMapBase: MapBase {
+    type Range<'a, K: 'a, V: 'a>: Iterator<Item = (&'a Ref<K>, &'a Ref<V>)>
+        + DoubleEndedIterator
+        + FusedIterator
+    where
+        Self: 'a;
+
+    fn first(&self) -> Option<(&Ref<Self::Key>, &Ref<Self::Val>)>;
+    fn last(&self) -> Option<(&Ref<Self::Key>, &Ref<Self::Val>)>;
+    fn pop_first(&mut self) -> Option<(Ref<Self::Key>, Ref<Self::Val>)>;
+    fn pop_last(&mut self) -> Option<(Ref<Self::Key>, Ref<Self::Val>)>;
+}
+
+pub trait OrderedMapExt<Q: ?Sized = <Self as MapBase>::Key>: OrderedMapBase + MapExt<Q> {
+    fn range<T>(&self, range: T) -> Self::Range<'_, Self::Key, Self::Val>
+    where
+        T: RangeBounds<Q>;
+    fn split_off(&mut self, key: &Q) -> Self;
+}
+
+pub trait OrderedMap: OrderedMapBase + OrderedMapExt {}
+
+impl<T> OrderedMap for T where T: OrderedMapBase + OrderedMapExt {}
+
+/// A map that finds a stored key exactly when it is equal to the key looked
+/// up by `Eq`, and that visits keys in `Ord` order if it is ordered at all.
+///
+/// `BiMap`'s `PartialEq` looks pairs up through the left map, so its
+/// comparison and hashing impls, which use `L`'s own traits, require this of
+/// the left map to stay consistent with it. A backend with a custom notion of
+/// key equality, like a case-insensitive comparator, must not implement it.
+pub trait NaturalMap: MapBase {}
+
 pub struct BiMap<LMap, RMap> {
     lmap: LMap,
     rmap: RMap,
+    auto_shrink: bool,
+}
+
+// An inner map counts as sparse, for `compact` and auto-shrinking, once fewer
+// than one in this many of its slots are in use. Each map is judged on its own,
+// since a backend like `BTreeMap` never has spare capacity.
+const SPARSE_RATIO: usize = 4;
+
+fn is_sparse<M: MapBase>(map: &M) -> bool {
+    map.len() * SPARSE_RATIO < map.capacity()
 }
 
 impl<L, R, LMap, RMap> BiMap<LMap, RMap>
@@ -92,9 +374,125 @@ where
         BiMap {
             lmap: LMap::new(),
             rmap: RMap::new(),
+            auto_shrink: false,
         }
     }
 
+    pub fn with_capacity(capacity: usize) -> Self {
+        BiMap {
+            lmap: LMap::with_capacity(capacity),
+            rmap: RMap::with_capacity(capacity),
+            auto_shrink: false,
+        }
+    }
+
+    /// Creates a bimap from its left and right maps, which must pass
+    /// [`BiMap::is_valid`]: each value's `Ref` in one map must be a clone of
+    /// its `Ref` in the other, with no further clones alive. Otherwise returns
+    /// the maps unchanged.
+    pub fn from_parts(lmap: LMap, rmap: RMap) -> Result<Self, (LMap, RMap)> {
+        let bimap = BiMap {
+            lmap,
+            rmap,
+            auto_shrink: false,
+        };
+        if bimap.is_valid() {
+            Ok(bimap)
+        } else {
+            Err((bimap.lmap, bimap.rmap))
+        }
+    }
+
+    /// Returns the left and right maps, the inverse of [`BiMap::from_parts`].
+    pub fn into_parts(self) -> (LMap, RMap) {
+        (self.lmap, self.rmap)
+    }
+
+    pub fn len(&self) -> usize {
+        debug_assert_eq!(self.lmap.len(), self.rmap.len());
+        self.lmap.len()
+    }
+
+    /// Returns the length of the left map, which should always equal that of
+    /// the right map. Useful for debugging custom backends.
+    #[cfg(any(test, feature = "debug-internals"))]
+    pub fn len_left(&self) -> usize {
+        self.lmap.len()
+    }
+
+    /// Returns the length of the right map. See [`BiMap::len_left`].
+    #[cfg(any(test, feature = "debug-internals"))]
+    pub fn len_right(&self) -> usize {
+        self.rmap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        debug_assert_eq!(self.lmap.is_empty(), self.rmap.is_empty());
+        self.lmap.is_empty()
+    }
+
+    /// Returns the number of pairs the bimap can hold without reallocating,
+    /// which is the smaller of the capacities of the two inner maps.
+    pub fn capacity(&self) -> usize {
+        self.lmap.capacity().min(self.rmap.capacity())
+    }
+
+    /// Returns the capacities of the left and right maps.
+    pub fn capacities(&self) -> (usize, usize) {
+        (self.lmap.capacity(), self.rmap.capacity())
+    }
+
+    /// Estimates the number of bytes the bimap has allocated on the heap,
+    /// ignoring any allocations owned by the values themselves and any
+    /// per-entry bookkeeping of the inner maps.
+    pub fn memory_usage(&self) -> usize {
+        // Each value lives in an `Rc` allocation alongside its two reference
+        // counts, and each inner map holds one pair of `Ref`s per slot.
+        let rc_overhead = 2 * mem::size_of::<usize>();
+        let slots = self.lmap.capacity() + self.rmap.capacity();
+        let values = self.len() * (2 * rc_overhead + mem::size_of::<L>() + mem::size_of::<R>());
+        slots * mem::size_of::<(Ref<L>, Ref<R>)>() + values
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.lmap.reserve(additional);
+        self.rmap.reserve(additional);
+    }
+
+    /// Like `reserve`, but also grows the smaller of the two maps to match
+    /// the larger one.
+    pub fn reserve_balanced(&mut self, additional: usize) {
+        let len = self.len();
+        let (lcap, rcap) = self.capacities();
+        let target = (len + additional).max(lcap).max(rcap);
+        self.lmap.reserve(target - len);
+        self.rmap.reserve(target - len);
+    }
+
+    /// Tries to reserve capacity for at least `additional` more pairs. If this
+    /// fails, the left map may still have grown.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.lmap.try_reserve(additional)?;
+        self.rmap.try_reserve(additional)
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.lmap.shrink_to_fit();
+        self.rmap.shrink_to_fit();
+    }
+
+    /// Shrinks the capacity of both maps, keeping at least room for
+    /// `min_capacity` pairs.
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.lmap.shrink_to(min_capacity);
+        self.rmap.shrink_to(min_capacity);
+    }
+
+    /// Returns the right value paired with `left`.
+    ///
+    /// As with `HashMap::get`, `left` may be any borrowed form of `L`: a
+    /// bimap with `String` left values can be queried with `&String` or
+    /// `&str`, and an owned `String` key is passed by reference.
     pub fn get_left<Q: ?Sized>(&self, left: &Q) -> Option<&R>
     where
         LMap: MapExt<Q>,
@@ -102,6 +500,8 @@ where
         self.lmap.get(left).map(|r| &**r)
     }
 
+    /// Returns the left value paired with `right`. See [`BiMap::get_left`]
+    /// for which query types are accepted.
     pub fn get_right<Q: ?Sized>(&self, right: &Q) -> Option<&L>
     where
         RMap: MapExt<Q>,
@@ -109,6 +509,105 @@ where
         self.rmap.get(right).map(|l| &**l)
     }
 
+    /// Like `get_left`, but fails with `KeyNotFound` if `left` is absent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generic_bimap::{BiBTreeMap, KeyNotFound};
+    ///
+    /// fn port_of(services: &BiBTreeMap<&str, u16>, name: &str) -> Result<u16, KeyNotFound> {
+    ///     let port = services.try_get_left(name)?;
+    ///     Ok(*port)
+    /// }
+    ///
+    /// let services: BiBTreeMap<_, _> = [("http", 80), ("ssh", 22)].into_iter().collect();
+    /// assert_eq!(port_of(&services, "ssh"), Ok(22));
+    /// assert_eq!(port_of(&services, "ftp"), Err(KeyNotFound));
+    /// ```
+    pub fn try_get_left<Q: ?Sized>(&self, left: &Q) -> Result<&R, KeyNotFound>
+    where
+        LMap: MapExt<Q>,
+    {
+        self.get_left(left).ok_or(KeyNotFound)
+    }
+
+    /// Like `get_right`, but fails with `KeyNotFound` if `right` is absent.
+    pub fn try_get_right<Q: ?Sized>(&self, right: &Q) -> Result<&L, KeyNotFound>
+    where
+        RMap: MapExt<Q>,
+    {
+        self.get_right(right).ok_or(KeyNotFound)
+    }
+
+    /// Returns the `Ref` to the right value paired with `left`. Cloning it
+    /// shares the value without copying it, but see
+    /// [`BiMap::remove_left`] for what that means for removal.
+    pub fn get_left_ref<Q: ?Sized>(&self, left: &Q) -> Option<&Ref<R>>
+    where
+        LMap: MapExt<Q>,
+    {
+        self.lmap.get(left)
+    }
+
+    /// Returns the `Ref` to the left value paired with `right`. See
+    /// [`BiMap::get_left_ref`].
+    pub fn get_right_ref<Q: ?Sized>(&self, right: &Q) -> Option<&Ref<L>>
+    where
+        RMap: MapExt<Q>,
+    {
+        self.rmap.get(right)
+    }
+
+    /// Returns a new `Ref` to the stored left value equal to `left`, sharing
+    /// its allocation. See [`BiMap::remove_left`] for what holding it means
+    /// for removal.
+    pub fn clone_left_ref<Q: ?Sized>(&self, left: &Q) -> Option<Ref<L>>
+    where
+        LMap: MapExt<Q>,
+    {
+        self.lmap.get_key_value(left).map(|(l, _)| Ref::clone(l))
+    }
+
+    /// Returns a new `Ref` to the stored right value equal to `right`. See
+    /// [`BiMap::clone_left_ref`].
+    pub fn clone_right_ref<Q: ?Sized>(&self, right: &Q) -> Option<Ref<R>>
+    where
+        RMap: MapExt<Q>,
+    {
+        self.rmap.get_key_value(right).map(|(r, _)| Ref::clone(r))
+    }
+
+    /// Looks `key` up as a left value, then as a right value. Returns the
+    /// value paired with it, as `Either::Left` if `key` is a left value or
+    /// `Either::Right` if it is a right value.
+    pub fn get_either<Q: ?Sized>(&self, key: &Q) -> Option<Either<&R, &L>>
+    where
+        LMap: MapExt<Q>,
+        RMap: MapExt<Q>,
+    {
+        match self.get_left(key) {
+            Some(r) => Some(Either::Left(r)),
+            None => self.get_right(key).map(Either::Right),
+        }
+    }
+
+    /// Returns the stored pair containing `left`.
+    pub fn get_left_full<Q: ?Sized>(&self, left: &Q) -> Option<(&L, &R)>
+    where
+        LMap: MapExt<Q>,
+    {
+        self.lmap.get_key_value(left).map(|(l, r)| (&**l, &**r))
+    }
+
+    /// Returns the stored pair containing `right`.
+    pub fn get_right_full<Q: ?Sized>(&self, right: &Q) -> Option<(&L, &R)>
+    where
+        RMap: MapExt<Q>,
+    {
+        self.rmap.get_key_value(right).map(|(r, l)| (&**l, &**r))
+    }
+
     pub fn contains_left<Q: ?Sized>(&self, left: &Q) -> bool
     where
         LMap: MapExt<Q>,
@@ -123,29 +622,1006 @@ where
         self.rmap.contains(right)
     }
 
+    /// Returns `true` if every value in `lefts` is a left value, stopping at
+    /// the first one that isn't.
+    pub fn contains_left_all<Q: ?Sized>(&self, lefts: &[&Q]) -> bool
+    where
+        LMap: MapExt<Q>,
+    {
+        lefts.iter().all(|&left| self.lmap.contains(left))
+    }
+
+    /// Returns `true` if any value in `lefts` is a left value, stopping at the
+    /// first one that is.
+    pub fn contains_left_any<Q: ?Sized>(&self, lefts: &[&Q]) -> bool
+    where
+        LMap: MapExt<Q>,
+    {
+        lefts.iter().any(|&left| self.lmap.contains(left))
+    }
+
+    /// Returns whether each value in `lefts` is a left value, in order.
+    pub fn contains_left_each<Q: ?Sized>(&self, lefts: &[&Q]) -> Vec<bool>
+    where
+        LMap: MapExt<Q>,
+    {
+        lefts.iter().map(|&left| self.lmap.contains(left)).collect()
+    }
+
+    /// Returns whether each value in `rights` is a right value, in order.
+    pub fn contains_right_each<Q: ?Sized>(&self, rights: &[&Q]) -> Vec<bool>
+    where
+        RMap: MapExt<Q>,
+    {
+        rights
+            .iter()
+            .map(|&right| self.rmap.contains(right))
+            .collect()
+    }
+
+    /// Looks up the right values paired with each of `lefts` at once.
+    pub fn get_many_left<Q: ?Sized, const N: usize>(&self, lefts: [&Q; N]) -> [Option<&R>; N]
+    where
+        LMap: MapExt<Q>,
+    {
+        lefts.map(|left| self.get_left(left))
+    }
+
+    /// Returns `true` if `left` and `right` are paired with each other.
+    pub fn contains_pair<Q: ?Sized, W: ?Sized + PartialEq>(&self, left: &Q, right: &W) -> bool
+    where
+        LMap: MapExt<Q>,
+        R: Borrow<W>,
+    {
+        self.lmap.get(left).is_some_and(|r| R::borrow(r) == right)
+    }
+
+    /// Checks that the left and right maps describe the same bijection, with
+    /// each pair's values shared between the two maps and by nothing else.
+    /// A value with an outstanding `Ref` clone fails the check, since removing
+    /// its pair would have to clone it.
+    pub fn is_valid(&self) -> bool {
+        self.lmap.len() == self.rmap.len()
+            && self.lmap.iter().all(|(l0, r0)| {
+                self.rmap.get_key_value(r0).is_some_and(|(r1, l1)| {
+                    Ref::ptr_eq(l0, l1)
+                        && Ref::ptr_eq(r0, r1)
+                        && Ref::strong_count(l0) == 2
+                        && Ref::strong_count(r0) == 2
+                })
+            })
+    }
+
+    /// Inserts the pair `(left, right)`, first removing any existing pairs
+    /// containing `left` or `right`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generic_bimap::{BiBTreeMap, Overwritten};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(bimap.insert(1, 'a'), Overwritten::Neither);
+    /// assert_eq!(bimap.insert(1, 'b'), Overwritten::Left(1, 'a'));
+    ///
+    /// // When the overwritten pairs don't matter:
+    /// bimap.insert_unchecked(2, 'b');
+    /// assert_eq!(bimap.get_left(&1), None);
+    /// assert_eq!(bimap.get_left(&2), Some(&'b'));
+    /// ```
+    #[must_use = "use `insert_unchecked` if the overwritten pairs aren't needed"]
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        let overwritten = self.remove_overlapping(&left, &right);
+        self.insert_raw(left, right);
+        overwritten.join()
+    }
+
+    // Removes any pairs containing `left` or `right`, as `insert` does, but
+    // leaves joining their `Ref`s to the caller.
+    fn remove_overlapping(&mut self, left: &L, right: &R) -> OverwrittenRefs<L, R> {
+        // Both lookups happen before either map is otherwise modified, which
+        // is enough to tell the cases apart without searching for `left` or
+        // `right` a second time.
+        let lpair: Option<(Ref<L>, Ref<R>)> = self.lmap.remove(left);
+        let rpair: Option<(Ref<R>, Ref<L>)> = self.rmap.remove(right);
+        match (lpair, rpair) {
+            (None, None) => Overwritten::Neither,
+            (Some((l0, r0)), None) => {
+                let (r1, l1) = self.rmap.remove(&r0).expect("bimap invariant");
+                Overwritten::Left((l0, l1), (r0, r1))
+            }
+            (None, Some((r0, l0))) => {
+                let (l1, r1) = self.lmap.remove(&l0).expect("bimap invariant");
+                Overwritten::Right((l0, l1), (r0, r1))
+            }
+            (Some((l0, r0)), Some((r1, l1))) if Ref::ptr_eq(&l0, &l1) => {
+                Overwritten::Pair((l0, l1), (r0, r1))
+            }
+            (Some((l0, r0)), Some((r2, l2))) => {
+                let (r1, l1) = self.rmap.remove(&r0).expect("bimap invariant");
+                let (l3, r3) = self.lmap.remove(&l2).expect("bimap invariant");
+                Overwritten::Both(((l0, l1), (r0, r1)), ((l2, l3), (r2, r3)))
+            }
+        }
+    }
+
+    /// Pairs `left` with `new_right` in place of its current right value,
+    /// which is returned. Fails without modifying the bimap if `left` is
+    /// absent or `new_right` is already paired with a different left value,
+    /// handing `new_right` back in the error.
+    pub fn replace_right<Q: ?Sized>(
+        &mut self,
+        left: &Q,
+        new_right: R,
+    ) -> Result<R, ReplaceRightError<R>>
+    where
+        L: Clone,
+        R: Clone,
+        LMap: MapExt<Q>,
+    {
+        match (self.lmap.get_key_value(left), self.rmap.get(&new_right)) {
+            (None, _) => return Err(ReplaceRightError::LeftNotFound(new_right)),
+            (Some((l0, _)), Some(l1)) if !Ref::ptr_eq(l0, l1) => {
+                return Err(ReplaceRightError::RightCollision(new_right));
+            }
+            _ => {}
+        }
+        let (left, old_right) = self.remove_left(left).expect("bimap invariant");
+        self.insert_raw(left, new_right);
+        Ok(old_right)
+    }
+
+    /// Swaps the right values paired with the left values `a` and `b`.
+    /// Returns `false` without modifying the bimap if either is absent.
+    /// Swapping a left value with itself does nothing.
+    pub fn swap_pair_left<Q: ?Sized>(&mut self, a: &Q, b: &Q) -> bool
+    where
+        LMap: MapExt<Q>,
+    {
+        let (la, ra) = match self.lmap.get_key_value(a) {
+            Some((l, r)) => (l.clone(), r.clone()),
+            None => return false,
+        };
+        let (lb, rb) = match self.lmap.get_key_value(b) {
+            Some((l, r)) => (l.clone(), r.clone()),
+            None => return false,
+        };
+        if Ref::ptr_eq(&la, &lb) {
+            return true;
+        }
+        // Every key is already present, so each insert replaces a value in
+        // place and nothing is ever missing from either map.
+        self.lmap.insert(la.clone(), rb.clone());
+        self.lmap.insert(lb.clone(), ra.clone());
+        self.rmap.insert(ra, lb);
+        self.rmap.insert(rb, la);
+        true
+    }
+
+    /// Inserts every pair from `iter` as with `insert`, returning how many of
+    /// them displaced an existing pair. Reinserting a pair that is already
+    /// present doesn't count.
+    pub fn insert_iter<I>(&mut self, iter: I) -> usize
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let iter = iter.into_iter();
+        // Like std's maps, only reserve half the hint when nonempty, in case
+        // many of the new pairs overwrite existing ones.
+        let additional = if self.is_empty() {
+            iter.size_hint().0
+        } else {
+            iter.size_hint().0.div_ceil(2)
+        };
+        self.reserve(additional);
+        let mut displaced = 0;
+        for (l, r) in iter {
+            match self.remove_overlapping(&l, &r) {
+                Overwritten::Neither | Overwritten::Pair(..) => {}
+                _ => displaced += 1,
+            }
+            self.insert_raw(l, r);
+        }
+        displaced
+    }
+
+    /// Like `insert`, but drops any overwritten pairs instead of returning
+    /// them.
+    pub fn insert_unchecked(&mut self, left: L, right: R) {
+        drop(self.remove_overlapping(&left, &right));
+        self.insert_raw(left, right);
+    }
+
+    /// Inserts the pair `(left, right)` only if neither value is already
+    /// present, otherwise returns the pair unchanged.
+    pub fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        if self.contains_left(&left) || self.contains_right(&right) {
+            return Err((left, right));
+        }
+        self.insert_raw(left, right);
+        Ok(())
+    }
+
+    /// Inserts the pair `(left, right)`, replacing the right value previously
+    /// paired with `left`, if any. Fails without modifying the bimap if
+    /// `right` is already paired with a different left value.
+    pub fn try_insert(&mut self, left: L, right: R) -> Result<Option<R>, (L, R)>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        let collides = match (self.lmap.get_key_value(&left), self.rmap.get(&right)) {
+            (_, None) => false,
+            (Some((l0, _)), Some(l1)) => !Ref::ptr_eq(l0, l1),
+            (None, Some(_)) => true,
+        };
+        if collides {
+            return Err((left, right));
+        }
+        let old = self.remove_left(&left).map(|(_, r)| r);
+        self.insert_raw(left, right);
+        Ok(old)
+    }
+
+    /// Builds a bimap from `iter` using `insert_no_overwrite`, returning it
+    /// along with every pair that was rejected, in iteration order.
+    pub fn from_iter_no_overwrite<I>(iter: I) -> (Self, Vec<(L, R)>)
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let mut bimap = BiMap::new();
+        let mut rejected = Vec::new();
+        for (left, right) in iter {
+            if let Err(pair) = bimap.insert_no_overwrite(left, right) {
+                rejected.push(pair);
+            }
+        }
+        (bimap, rejected)
+    }
+
+    // Assumes neither `left` nor `right` is present.
+    fn insert_raw(&mut self, left: L, right: R) {
+        let (l0, l1) = Ref::split(left);
+        let (r0, r1) = Ref::split(right);
+        self.lmap.insert(l0, r0);
+        self.rmap.insert(r1, l1);
+    }
+
+    // Like `insert_raw`, but also returns a `Weak` to the new left value.
+    fn insert_raw_weak(&mut self, left: L, right: R) -> Weak<L> {
+        let (l0, l1) = Ref::split(left);
+        let (r0, r1) = Ref::split(right);
+        let weak = Ref::downgrade(&l0);
+        self.lmap.insert(l0, r0);
+        self.rmap.insert(r1, l1);
+        weak
+    }
+
+    // Assumes the bimap is empty and none of the values are repeated.
+    fn extend_raw<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let lpairs = iter
+            .into_iter()
+            .map(|(left, right)| (Ref::new(left), Ref::new(right)));
+        self.lmap.extend_pairs(lpairs);
+        // The right map shares its `Rc`s with the left map, so it is filled
+        // from the left map rather than from a buffer of the new pairs.
+        let rpairs = self.lmap.iter().map(|(l, r)| (r.clone(), l.clone()));
+        self.rmap.extend_pairs(rpairs);
+    }
+
+    /// Removes the pair `(left, right)` only if `left` and `right` are paired
+    /// with each other.
+    pub fn remove_pair<Q: ?Sized, W: ?Sized + PartialEq>(
+        &mut self,
+        left: &Q,
+        right: &W,
+    ) -> Option<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+        LMap: MapExt<Q>,
+        R: Borrow<W>,
+    {
+        if self.contains_pair(left, right) {
+            self.remove_left(left)
+        } else {
+            None
+        }
+    }
+
+    /// Moves every pair from `other` into `self`, leaving `other` empty. Pairs
+    /// from `other` overwrite any pairs in `self` they conflict with.
+    pub fn append(&mut self, other: &mut Self)
+    where
+        L: Clone,
+        R: Clone,
+    {
+        self.extend(other.drain());
+    }
+
+    /// Swaps the roles of the left and right values.
+    pub fn inverse(self) -> BiMap<RMap, LMap> {
+        BiMap {
+            lmap: self.rmap,
+            rmap: self.lmap,
+            auto_shrink: self.auto_shrink,
+        }
+    }
+
+    /// Removes every pair `(l, r)` for which `f(&l, &r)` returns `false`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&L, &R) -> bool,
+    {
+        // Mirror each removal from `lmap` onto `rmap` as `lmap` drives.
+        let rmap = &mut self.rmap;
+        self.lmap.retain(|l, r| {
+            let keep = f(l, r);
+            if !keep {
+                rmap.remove(r).expect("bimap invariant");
+            }
+            keep
+        });
+        self.maybe_shrink();
+    }
+
+    /// Returns the pairs present in both `self` and `other`.
+    pub fn intersection(&self, other: &Self) -> Self
+    where
+        L: Clone,
+        R: Clone + PartialEq,
+    {
+        let mut bimap = BiMap::new();
+        for (l, r) in self.lmap.iter() {
+            if other.contains_pair(&**l, &**r) {
+                bimap.insert_raw(L::clone(l), R::clone(r));
+            }
+        }
+        bimap
+    }
+
+    /// Returns the pairs present in either `self` or `other`. A pair of
+    /// `other` sharing a value with a different pair of `self` is skipped.
+    pub fn union(&self, other: &Self) -> Self
+    where
+        L: Clone,
+        R: Clone,
+    {
+        let mut bimap = self.clone();
+        for (l, r) in other.lmap.iter() {
+            if !bimap.contains_left(&**l) && !bimap.contains_right(&**r) {
+                bimap.insert_raw(L::clone(l), R::clone(r));
+            }
+        }
+        bimap
+    }
+
+    /// Returns the pairs present in exactly one of `self` and `other`. As in
+    /// `union`, a pair of `other` sharing a value with a pair of `self` that
+    /// is kept gets skipped.
+    pub fn symmetric_difference(&self, other: &Self) -> Self
+    where
+        L: Clone,
+        R: Clone + PartialEq,
+    {
+        let mut bimap = BiMap::new();
+        for (l, r) in self.lmap.iter() {
+            if !other.contains_pair(&**l, &**r) {
+                bimap.insert_raw(L::clone(l), R::clone(r));
+            }
+        }
+        for (l, r) in other.lmap.iter() {
+            if !self.contains_pair(&**l, &**r)
+                && !bimap.contains_left(&**l)
+                && !bimap.contains_right(&**r)
+            {
+                bimap.insert_raw(L::clone(l), R::clone(r));
+            }
+        }
+        bimap
+    }
+
+    /// Rebuilds the bimap from `f(l, r)` for each pair `(l, r)`, dropping the
+    /// pair if `f` returns `None`. New pairs are inserted in iteration order
+    /// with `insert`, so a later pair overwrites any earlier ones it collides
+    /// with.
+    pub fn remap<F>(self, mut f: F) -> Self
+    where
+        L: Clone,
+        R: Clone,
+        F: FnMut(L, R) -> Option<(L, R)>,
+    {
+        let mut bimap = BiMap::with_capacity(self.len());
+        bimap.auto_shrink = self.auto_shrink;
+        for (l, r) in self {
+            if let Some((l, r)) = f(l, r) {
+                bimap.insert_unchecked(l, r);
+            }
+        }
+        bimap
+    }
+
+    /// Splits the bimap into the pairs `(l, r)` for which `f(&l, &r)` returns
+    /// `true` and those for which it returns `false`.
+    pub fn partition<F>(self, mut f: F) -> (Self, Self)
+    where
+        L: Clone,
+        R: Clone,
+        F: FnMut(&L, &R) -> bool,
+    {
+        let mut yes = BiMap::new();
+        let mut no = BiMap::new();
+        yes.auto_shrink = self.auto_shrink;
+        no.auto_shrink = self.auto_shrink;
+        for (l, r) in self {
+            if f(&l, &r) {
+                yes.insert_raw(l, r);
+            } else {
+                no.insert_raw(l, r);
+            }
+        }
+        (yes, no)
+    }
+
+    /// Returns an iterator that removes and yields every pair `(l, r)` for
+    /// which `pred(&l, &r)` returns `true`.
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, LMap, RMap, F>
+    where
+        F: FnMut(&L, &R) -> bool,
+    {
+        // Snapshot the left values up front, since pairs can't be removed from
+        // the maps while iterating over them.
+        let lefts: Vec<Ref<L>> = self.lmap.iter().map(|(l, _)| l.clone()).collect();
+        ExtractIf {
+            bimap: self,
+            lefts: lefts.into_iter(),
+            pred,
+        }
+    }
+
+    /// Removes every pair whose left value `l` has `f(&l)` return `false`.
+    pub fn retain_left<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&L) -> bool,
+    {
+        self.retain(|l, _| f(l));
+    }
+
+    /// Removes every pair whose right value `r` has `f(&r)` return `false`.
+    pub fn retain_right<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&R) -> bool,
+    {
+        self.retain(|_, r| f(r));
+    }
+
+    /// Replaces each right value `r` with `f(&l, r)`, where `l` is its left
+    /// value. Pairs are reinserted in iteration order, so if two new right
+    /// values are equal, the later pair is removed and returned instead.
+    ///
+    /// Since both maps are rebuilt, this costs about as much as draining the
+    /// bimap and collecting it again.
+    pub fn update_rights<F>(&mut self, mut f: F) -> Vec<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+        F: FnMut(&L, R) -> R,
+    {
+        let pairs: Vec<(L, R)> = self.drain().collect();
+        let mut rejected = Vec::new();
+        for (left, right) in pairs {
+            let right = f(&left, right);
+            if let Err(pair) = self.insert_no_overwrite(left, right) {
+                rejected.push(pair);
+            }
+        }
+        rejected
+    }
+
+    /// Like `retain`, but also passes each pair's position in ascending order
+    /// of left value, counting from zero, to `f`.
+    pub fn retain_ordered<F>(&mut self, mut f: F)
+    where
+        LMap: OrderedMapBase,
+        F: FnMut(usize, &L, &R) -> bool,
+    {
+        let mut index = 0;
+        self.retain(|l, r| {
+            let keep = f(index, l, r);
+            index += 1;
+            keep
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.lmap.clear();
+        self.rmap.clear();
+    }
+
+    /// Sets whether the bimap shrinks its capacity automatically once it is
+    /// mostly empty. Disabled by default.
+    ///
+    /// When enabled, this is checked after every method that removes pairs,
+    /// with two exceptions. `clear` keeps the capacity, as it is meant for
+    /// reusing the bimap, and [`BiMap::clear_and_shrink`] frees it instead.
+    /// `insert` and its variants only remove pairs to make room for the one
+    /// being inserted, so they never leave the bimap sparser than before.
+    pub fn enable_auto_shrink(&mut self, enabled: bool) {
+        self.auto_shrink = enabled;
+        self.maybe_shrink();
+    }
+
+    /// Shrinks each map to fit, but only if its capacity is more than four
+    /// times the bimap's length.
+    pub fn compact(&mut self) {
+        if is_sparse(&self.lmap) {
+            self.lmap.shrink_to_fit();
+        }
+        if is_sparse(&self.rmap) {
+            self.rmap.shrink_to_fit();
+        }
+    }
+
+    /// Removes every pair and frees as much memory as possible.
+    pub fn clear_and_shrink(&mut self) {
+        self.clear();
+        self.shrink_to_fit();
+    }
+
+    /// Removes every pair and resizes both maps to hold about `capacity`
+    /// pairs.
+    pub fn reset_to_capacity(&mut self, capacity: usize) {
+        self.clear();
+        self.shrink_to(capacity);
+        self.reserve(capacity);
+    }
+
+    /// Removes the pair containing `left`.
     pub fn remove_left<Q: ?Sized>(&mut self, left: &Q) -> Option<(L, R)>
     where
+        L: Clone,
+        R: Clone,
         LMap: MapExt<Q>,
     {
         let (l0, r0): (Ref<L>, Ref<R>) = self.lmap.remove(left)?;
         let (r1, l1): (Ref<R>, Ref<L>) = self.rmap.remove(&r0).expect("bimap invariant");
         let left = Ref::join(l0, l1);
         let right = Ref::join(r0, r1);
+        self.maybe_shrink();
         Some((left, right))
     }
 
+    /// Removes the pair containing `right`.
     pub fn remove_right<Q: ?Sized>(&mut self, right: &Q) -> Option<(L, R)>
     where
+        L: Clone,
+        R: Clone,
         RMap: MapExt<Q>,
     {
         let (r0, l0): (Ref<R>, Ref<L>) = self.rmap.remove(right)?;
         let (l1, r1): (Ref<L>, Ref<R>) = self.lmap.remove(&l0).expect("bimap invariant");
         let left = Ref::join(l0, l1);
         let right = Ref::join(r0, r1);
+        self.maybe_shrink();
+        Some((left, right))
+    }
+
+    /// Removes the pair containing the left value of `left`, typically a
+    /// `Ref` cloned out of the bimap earlier. Taking it by value lets it be
+    /// dropped before the values are moved out.
+    pub fn remove_left_ref(&mut self, left: Ref<L>) -> Option<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        let (l0, r0): (Ref<L>, Ref<R>) = self.lmap.remove(&left)?;
+        drop(left);
+        let (r1, l1): (Ref<R>, Ref<L>) = self.rmap.remove(&r0).expect("bimap invariant");
+        let left = Ref::join(l0, l1);
+        let right = Ref::join(r0, r1);
+        self.maybe_shrink();
+        Some((left, right))
+    }
+
+    /// Removes the pair containing the right value of `right`. See
+    /// [`BiMap::remove_left_ref`].
+    pub fn remove_right_ref(&mut self, right: Ref<R>) -> Option<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        let (r0, l0): (Ref<R>, Ref<L>) = self.rmap.remove(&right)?;
+        drop(right);
+        let (l1, r1): (Ref<L>, Ref<R>) = self.lmap.remove(&l0).expect("bimap invariant");
+        let left = Ref::join(l0, l1);
+        let right = Ref::join(r0, r1);
+        self.maybe_shrink();
+        Some((left, right))
+    }
+
+    /// Removes the pair containing `left`, returning `Ref`s to its values
+    /// rather than moving them out. Unlike [`BiMap::remove_left`] this never
+    /// clones the values; they can be recovered with [`Ref::try_into_inner`]
+    /// once any other clones are dropped.
+    pub fn remove_left_shared<Q: ?Sized>(&mut self, left: &Q) -> Option<(Ref<L>, Ref<R>)>
+    where
+        LMap: MapExt<Q>,
+    {
+        let (l0, r0): (Ref<L>, Ref<R>) = self.lmap.remove(left)?;
+        self.rmap.remove(&r0).expect("bimap invariant");
+        self.maybe_shrink();
+        Some((l0, r0))
+    }
+
+    /// Removes the pair containing `right`, returning `Ref`s to its values.
+    /// See [`BiMap::remove_left_shared`].
+    pub fn remove_right_shared<Q: ?Sized>(&mut self, right: &Q) -> Option<(Ref<L>, Ref<R>)>
+    where
+        RMap: MapExt<Q>,
+    {
+        let (r0, l0): (Ref<R>, Ref<L>) = self.rmap.remove(right)?;
+        self.lmap.remove(&l0).expect("bimap invariant");
+        self.maybe_shrink();
+        Some((l0, r0))
+    }
+
+    /// Returns the pair with the smallest left value.
+    pub fn first(&self) -> Option<(&L, &R)>
+    where
+        LMap: OrderedMapBase,
+    {
+        self.lmap.first().map(|(l, r)| (&**l, &**r))
+    }
+
+    /// Returns the pair with the largest left value.
+    pub fn last(&self) -> Option<(&L, &R)>
+    where
+        LMap: OrderedMapBase,
+    {
+        self.lmap.last().map(|(l, r)| (&**l, &**r))
+    }
+
+    /// Returns the pair with the smallest left value greater than `left`.
+    pub fn next_left_after<Q: ?Sized>(&self, left: &Q) -> Option<(&L, &R)>
+    where
+        LMap: OrderedMapExt<Q>,
+    {
+        let mut range = self.lmap.range((Bound::Excluded(left), Bound::Unbounded));
+        range.next().map(|(l, r)| (&**l, &**r))
+    }
+
+    /// Returns the pair with the smallest right value greater than `right`.
+    pub fn next_right_after<Q: ?Sized>(&self, right: &Q) -> Option<(&L, &R)>
+    where
+        RMap: OrderedMapExt<Q>,
+    {
+        let mut range = self.rmap.range((Bound::Excluded(right), Bound::Unbounded));
+        range.next().map(|(r, l)| (&**l, &**r))
+    }
+
+    /// Removes and returns the pair with the smallest left value.
+    pub fn pop_first(&mut self) -> Option<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+        LMap: OrderedMapBase,
+    {
+        let (l0, r0): (Ref<L>, Ref<R>) = self.lmap.pop_first()?;
+        let (r1, l1): (Ref<R>, Ref<L>) = self.rmap.remove(&r0).expect("bimap invariant");
+        let left = Ref::join(l0, l1);
+        let right = Ref::join(r0, r1);
+        self.maybe_shrink();
+        Some((left, right))
+    }
+
+    /// Splits the bimap in two at `left`, returning a new bimap containing
+    /// every pair whose left value is greater than or equal to `left`.
+    pub fn split_off<Q: ?Sized>(&mut self, left: &Q) -> Self
+    where
+        LMap: OrderedMapExt<Q>,
+    {
+        let lmap = self.lmap.split_off(left);
+        let mut rmap = RMap::with_capacity(lmap.len());
+        for (_, r) in lmap.iter() {
+            let (r0, l0) = self.rmap.remove(r).expect("bimap invariant");
+            rmap.insert(r0, l0);
+        }
+        self.maybe_shrink();
+        BiMap {
+            lmap,
+            rmap,
+            auto_shrink: self.auto_shrink,
+        }
+    }
+
+    /// Removes every pair whose left value lies in `range`, returning an
+    /// iterator over them in ascending order of left value. The pairs are
+    /// all removed up front, even if the iterator is dropped unused.
+    pub fn drain_left_range<Q: ?Sized, T>(&mut self, range: T) -> alloc::vec::IntoIter<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+        LMap: OrderedMapExt<Q>,
+        T: RangeBounds<Q>,
+    {
+        let rights: Vec<Ref<R>> = self.lmap.range(range).map(|(_, r)| r.clone()).collect();
+        let pairs: Vec<(L, R)> = rights
+            .into_iter()
+            .map(|right| {
+                let (r0, l0) = self.rmap.remove(&right).expect("bimap invariant");
+                drop(right);
+                let (l1, r1) = self.lmap.remove(&*l0).expect("bimap invariant");
+                (Ref::join(l0, l1), Ref::join(r0, r1))
+            })
+            .collect();
+        self.maybe_shrink();
+        pairs.into_iter()
+    }
+
+    /// Removes every pair whose right value lies in `range`, returning an
+    /// iterator over them in ascending order of right value. See
+    /// [`BiMap::drain_left_range`].
+    pub fn drain_right_range<Q: ?Sized, T>(&mut self, range: T) -> alloc::vec::IntoIter<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+        RMap: OrderedMapExt<Q>,
+        T: RangeBounds<Q>,
+    {
+        let lefts: Vec<Ref<L>> = self.rmap.range(range).map(|(_, l)| l.clone()).collect();
+        let pairs: Vec<(L, R)> = lefts
+            .into_iter()
+            .map(|left| {
+                let (l0, r0) = self.lmap.remove(&left).expect("bimap invariant");
+                drop(left);
+                let (r1, l1) = self.rmap.remove(&*r0).expect("bimap invariant");
+                (Ref::join(l0, l1), Ref::join(r0, r1))
+            })
+            .collect();
+        self.maybe_shrink();
+        pairs.into_iter()
+    }
+
+    /// Removes and returns the pair with the largest left value.
+    pub fn pop_last(&mut self) -> Option<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+        LMap: OrderedMapBase,
+    {
+        let (l0, r0): (Ref<L>, Ref<R>) = self.lmap.pop_last()?;
+        let (r1, l1): (Ref<R>, Ref<L>) = self.rmap.remove(&r0).expect("bimap invariant");
+        let left = Ref::join(l0, l1);
+        let right = Ref::join(r0, r1);
+        self.maybe_shrink();
         Some((left, right))
     }
 }
 
+impl<LMap: MapBase, RMap: MapBase> BiMap<LMap, RMap> {
+    fn maybe_shrink(&mut self) {
+        if !self.auto_shrink {
+            return;
+        }
+        // Leave some room so that shrinking doesn't happen again right away.
+        let min_capacity = self.lmap.len() * 2;
+        if is_sparse(&self.lmap) {
+            self.lmap.shrink_to(min_capacity);
+        }
+        if is_sparse(&self.rmap) {
+            self.rmap.shrink_to(min_capacity);
+        }
+    }
+}
+
+impl<L, R, LMap, RMap> Clone for BiMap<LMap, RMap>
+where
+    L: Clone,
+    R: Clone,
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    fn clone(&self) -> Self {
+        // Each pair gets fresh allocations so that the clone doesn't share any
+        // `Rc`s with `self`.
+        let mut bimap = BiMap::new();
+        bimap.auto_shrink = self.auto_shrink;
+        bimap.extend_raw(self.lmap.iter().map(|(l, r)| (L::clone(l), R::clone(r))));
+        bimap
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        // Clearing keeps the existing allocations around for reuse.
+        self.clear();
+        self.auto_shrink = source.auto_shrink;
+        self.reserve(source.len());
+        self.extend_raw(source.lmap.iter().map(|(l, r)| (L::clone(l), R::clone(r))));
+    }
+}
+
+impl<L, R, LMap, RMap> Default for BiMap<LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    fn default() -> Self {
+        BiMap::new()
+    }
+}
+
+impl<L, R, LMap, RMap, Q: ?Sized> Index<Left<'_, Q>> for BiMap<LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R> + MapExt<Q>,
+    RMap: Map<Key = R, Val = L>,
+{
+    type Output = R;
+
+    fn index(&self, index: Left<'_, Q>) -> &R {
+        self.get_left(index.0).expect("left value not in bimap")
+    }
+}
+
+impl<L, R, LMap, RMap, Q: ?Sized> Index<Right<'_, Q>> for BiMap<LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L> + MapExt<Q>,
+{
+    type Output = L;
+
+    fn index(&self, index: Right<'_, Q>) -> &L {
+        self.get_right(index.0).expect("right value not in bimap")
+    }
+}
+
+impl<L, R, LMap, RMap> PartialEq for BiMap<LMap, RMap>
+where
+    R: PartialEq,
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .lmap
+                .iter()
+                .all(|(l, r)| other.get_left(&**l) == Some(&**r))
+    }
+}
+
+impl<L, R, LMap, RMap> Eq for BiMap<LMap, RMap>
+where
+    R: Eq,
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+}
+
+/// Ordered bimaps compare their pairs lexicographically in order of left value.
+impl<L, R, LMap, RMap> PartialOrd for BiMap<LMap, RMap>
+where
+    L: PartialOrd,
+    R: PartialOrd,
+    LMap: OrderedMap<Key = L, Val = R> + NaturalMap,
+    RMap: Map<Key = R, Val = L>,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<L, R, LMap, RMap> Ord for BiMap<LMap, RMap>
+where
+    L: Ord,
+    R: Ord,
+    LMap: OrderedMap<Key = L, Val = R> + NaturalMap,
+    RMap: Map<Key = R, Val = L>,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+// An FNV-1a hasher for the pairs of a bimap, which unlike `DefaultHasher`
+// is available without `std`.
+struct PairHasher(u64);
+
+impl Default for PairHasher {
+    fn default() -> Self {
+        PairHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for PairHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+impl<LMap, RMap> Hash for BiMap<LMap, RMap>
+where
+    LMap: MapBase + NaturalMap,
+    LMap::Key: Hash,
+    LMap::Val: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Equal bimaps may iterate in different orders, so the pairs' hashes
+        // are combined with a commutative operation.
+        let sum = self
+            .lmap
+            .iter()
+            .map(|(l, r)| {
+                let mut hasher = PairHasher::default();
+                (&**l, &**r).hash(&mut hasher);
+                hasher.finish()
+            })
+            .fold(0, u64::wrapping_add);
+        state.write_usize(self.lmap.len());
+        state.write_u64(sum);
+    }
+}
+
+/// Later pairs overwrite any earlier pairs they conflict with, as if by
+/// repeated calls to [`BiMap::insert`].
+impl<L, R, LMap, RMap> FromIterator<(L, R)> for BiMap<LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    fn from_iter<I: IntoIterator<Item = (L, R)>>(iter: I) -> Self {
+        let mut bimap = BiMap::new();
+        bimap.extend(iter);
+        bimap
+    }
+}
+
+/// Later pairs overwrite any earlier pairs they conflict with, as if by
+/// repeated calls to [`BiMap::insert`].
+impl<L, R, LMap, RMap> Extend<(L, R)> for BiMap<LMap, RMap>
+where
+    LMap: Map<Key = L, Val = R>,
+    RMap: Map<Key = R, Val = L>,
+{
+    fn extend<I: IntoIterator<Item = (L, R)>>(&mut self, iter: I) {
+        self.insert_iter(iter);
+    }
+}
+
+impl<LMap, RMap> fmt::Debug for BiMap<LMap, RMap>
+where
+    LMap: MapBase,
+    LMap::Key: fmt::Debug,
+    LMap::Val: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Pair<'a, L, R>(&'a L, &'a R);
+
+        impl<L: fmt::Debug, R: fmt::Debug> fmt::Debug for Pair<'_, L, R> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)?;
+                f.write_str(" <-> ")?;
+                self.1.fmt(f)
+            }
+        }
+
+        f.debug_set()
+            .entries(self.lmap.iter().map(|(l, r)| Pair(&**l, &**r)))
+            .finish()
+    }
+}
+
 pub trait MapKind<K, V> {
     type Map: Map<Key = K, Val = V>;
 }
@@ -153,4 +1629,17 @@ pub trait MapKind<K, V> {
 pub type GenericBiMap<L, R, LKind, RKind> =
     BiMap<<LKind as MapKind<L, R>>::Map, <RKind as MapKind<R, L>>::Map>;
 
-pub type BiHashMap<L, R> = GenericBiMap<L, R, HashMapKind, HashMapKind>;
+#[cfg(feature = "std")]
+pub type BiHashMap<L, R, LS = RandomState, RS = RandomState> =
+    GenericBiMap<L, R, HashMapKind<LS>, HashMapKind<RS>>;
+
+pub type BiBTreeMap<L, R> = GenericBiMap<L, R, BTreeMapKind, BTreeMapKind>;
+
+/// A bimap whose left and right values are kept in sorted `Vec`s, ordered by
+/// the comparators `LC` and `RC`.
+pub type BiSortedVecMap<L, R, LC = NaturalOrder, RC = NaturalOrder> =
+    GenericBiMap<L, R, SortedVecMapKind<LC>, SortedVecMapKind<RC>>;
+
+/// A bimap with hashed left values and ordered right values.
+#[cfg(feature = "std")]
+pub type BiHashBTreeMap<L, R, S = RandomState> = GenericBiMap<L, R, HashMapKind<S>, BTreeMapKind>;
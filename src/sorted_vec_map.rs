@@ -0,0 +1,385 @@
+use alloc::vec::{self, Vec};
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Bound, RangeBounds};
+use core::slice;
+
+use crate::{
+    BiMap, MapBase, MapExt, MapKind, NaturalMap, OrderedMapBase, OrderedMapExt, Ref,
+    TryReserveError,
+};
+
+/// An ordering on `T` used in place of `Ord` by a `SortedVecMapKind` backend.
+///
+/// A comparator used to look up a borrowed form `Q` of a key type `K` must
+/// order `Q`s the same way it orders the `K`s they are borrowed from.
+pub trait Comparator<T: ?Sized> {
+    fn compare(a: &T, b: &T) -> Ordering;
+}
+
+/// The comparator that uses `Ord`.
+pub struct NaturalOrder {
+    _private: (),
+}
+
+impl<T: ?Sized + Ord> Comparator<T> for NaturalOrder {
+    fn compare(a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// A backend that keeps its entries in a `Vec` sorted by the comparator `C`,
+/// using binary search for lookups.
+pub struct SortedVecMapKind<C = NaturalOrder> {
+    marker: PhantomData<C>,
+}
+
+impl<K, V, C> MapKind<K, V> for SortedVecMapKind<C>
+where
+    C: Comparator<K>,
+{
+    type Map = SortedVecMap<K, V, C>;
+}
+
+pub struct SortedVecMap<K, V, C = NaturalOrder> {
+    entries: Vec<(Ref<K>, Ref<V>)>,
+    marker: PhantomData<C>,
+}
+
+impl<K, V, C> SortedVecMap<K, V, C> {
+    fn from_entries(entries: Vec<(Ref<K>, Ref<V>)>) -> Self {
+        Self {
+            entries,
+            marker: PhantomData,
+        }
+    }
+
+    // Returns the index of the first entry whose key isn't less than `key`
+    // (if `or_equal` is `false`) or greater than `key` (if it is `true`).
+    fn partition_point<Q: ?Sized>(&self, key: &Q, or_equal: bool) -> usize
+    where
+        K: Borrow<Q>,
+        C: Comparator<Q>,
+    {
+        self.entries
+            .partition_point(|(k, _)| match C::compare(K::borrow(k), key) {
+                Ordering::Less => true,
+                Ordering::Equal => or_equal,
+                Ordering::Greater => false,
+            })
+    }
+
+    fn search<Q: ?Sized>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        C: Comparator<Q>,
+    {
+        self.entries
+            .binary_search_by(|(k, _)| C::compare(K::borrow(k), key))
+    }
+}
+
+impl<K, V, C> MapBase for SortedVecMap<K, V, C>
+where
+    C: Comparator<K>,
+{
+    type Key = K;
+    type Val = V;
+    type Iter<'a, K_: 'a, V_: 'a> = Iter<'a, K_, V_> where Self: 'a;
+    type IntoIter<K_, V_> = IntoIter<K_, V_>;
+    type Drain<'a, K_: 'a, V_: 'a> = Drain<'a, K_, V_> where Self: 'a;
+
+    fn new() -> Self {
+        Self::from_entries(Vec::new())
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self::from_entries(Vec::with_capacity(capacity))
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.entries.try_reserve(additional)
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.entries.shrink_to_fit();
+    }
+
+    fn shrink_to(&mut self, min_capacity: usize) {
+        self.entries.shrink_to(min_capacity);
+    }
+
+    fn iter(&self) -> Self::Iter<'_, Self::Key, Self::Val> {
+        Iter {
+            iter: self.entries.iter(),
+        }
+    }
+
+    fn insert(&mut self, key: Ref<Self::Key>, val: Ref<Self::Val>) {
+        match self.search(&*key) {
+            Ok(i) => self.entries[i] = (key, val),
+            Err(i) => self.entries.insert(i, (key, val)),
+        }
+    }
+
+    fn extend_pairs<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (Ref<Self::Key>, Ref<Self::Val>)>,
+    {
+        // Sorting once is cheaper than inserting one entry at a time. The sort
+        // is stable, so of any entries with equal keys, the one inserted last
+        // comes last and is the one kept.
+        self.entries.extend(iter);
+        self.entries.sort_by(|(a, _), (b, _)| C::compare(a, b));
+        self.entries.dedup_by(|later, earlier| {
+            let equal = C::compare(&later.0, &earlier.0) == Ordering::Equal;
+            if equal {
+                mem::swap(later, earlier);
+            }
+            equal
+        });
+    }
+
+    fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Self::Key, &Self::Val) -> bool,
+    {
+        self.entries.retain(|(k, v)| f(k, v));
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn drain(&mut self) -> Self::Drain<'_, Self::Key, Self::Val> {
+        Drain {
+            iter: self.entries.drain(..),
+        }
+    }
+
+    fn into_iter(self) -> Self::IntoIter<Self::Key, Self::Val> {
+        IntoIter {
+            iter: self.entries.into_iter(),
+        }
+    }
+}
+
+impl<K, V, C, Q: ?Sized> MapExt<Q> for SortedVecMap<K, V, C>
+where
+    K: Borrow<Q>,
+    C: Comparator<K> + Comparator<Q>,
+{
+    fn get(&self, key: &Q) -> Option<&Ref<Self::Val>> {
+        let i = self.search(key).ok()?;
+        Some(&self.entries[i].1)
+    }
+
+    fn get_key_value(&self, key: &Q) -> Option<(&Ref<Self::Key>, &Ref<Self::Val>)> {
+        let i = self.search(key).ok()?;
+        let (k, v) = &self.entries[i];
+        Some((k, v))
+    }
+
+    fn contains(&self, key: &Q) -> bool {
+        self.search(key).is_ok()
+    }
+
+    fn remove(&mut self, key: &Q) -> Option<(Ref<Self::Key>, Ref<Self::Val>)> {
+        let i = self.search(key).ok()?;
+        Some(self.entries.remove(i))
+    }
+}
+
+impl<K: Ord, V> NaturalMap for SortedVecMap<K, V, NaturalOrder> {}
+
+impl<K, V, C> OrderedMapBase for SortedVecMap<K, V, C>
+where
+    C: Comparator<K>,
+{
+    type Range<'a, K_: 'a, V_: 'a> = Iter<'a, K_, V_> where Self: 'a;
+
+    fn first(&self) -> Option<(&Ref<Self::Key>, &Ref<Self::Val>)> {
+        self.entries.first().map(|(k, v)| (k, v))
+    }
+
+    fn last(&self) -> Option<(&Ref<Self::Key>, &Ref<Self::Val>)> {
+        self.entries.last().map(|(k, v)| (k, v))
+    }
+
+    fn pop_first(&mut self) -> Option<(Ref<Self::Key>, Ref<Self::Val>)> {
+        if self.entries.is_empty() {
+            None
+        } else {
+            Some(self.entries.remove(0))
+        }
+    }
+
+    fn pop_last(&mut self) -> Option<(Ref<Self::Key>, Ref<Self::Val>)> {
+        self.entries.pop()
+    }
+}
+
+impl<K, V, C, Q: ?Sized> OrderedMapExt<Q> for SortedVecMap<K, V, C>
+where
+    K: Borrow<Q>,
+    C: Comparator<K> + Comparator<Q>,
+{
+    fn range<T>(&self, range: T) -> Self::Range<'_, Self::Key, Self::Val>
+    where
+        T: RangeBounds<Q>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(key) => self.partition_point(key, false),
+            Bound::Excluded(key) => self.partition_point(key, true),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(key) => self.partition_point(key, true),
+            Bound::Excluded(key) => self.partition_point(key, false),
+            Bound::Unbounded => self.entries.len(),
+        };
+        Iter {
+            iter: self.entries[start..end.max(start)].iter(),
+        }
+    }
+
+    fn split_off(&mut self, key: &Q) -> Self {
+        let at = self.partition_point(key, false);
+        Self::from_entries(self.entries.split_off(at))
+    }
+}
+
+impl<'a, L, R, LC, RC> Extend<(&'a L, &'a R)>
+    for BiMap<SortedVecMap<L, R, LC>, SortedVecMap<R, L, RC>>
+where
+    L: Clone + 'a,
+    R: Clone + 'a,
+    LC: Comparator<L>,
+    RC: Comparator<R>,
+{
+    fn extend<I: IntoIterator<Item = (&'a L, &'a R)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(l, r)| (l.clone(), r.clone())));
+    }
+}
+
+impl<'a, L, R, LC, RC> FromIterator<(&'a L, &'a R)>
+    for BiMap<SortedVecMap<L, R, LC>, SortedVecMap<R, L, RC>>
+where
+    L: Clone + 'a,
+    R: Clone + 'a,
+    LC: Comparator<L>,
+    RC: Comparator<R>,
+{
+    fn from_iter<I: IntoIterator<Item = (&'a L, &'a R)>>(iter: I) -> Self {
+        let mut bimap = BiMap::new();
+        bimap.extend(iter);
+        bimap
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    iter: slice::Iter<'a, (Ref<K>, Ref<V>)>,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Iter {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a Ref<K>, &'a Ref<V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (k, v) = self.iter.next()?;
+        Some((k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (k, v) = self.iter.next_back()?;
+        Some((k, v))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for Iter<'a, K, V> {}
+
+pub struct IntoIter<K, V> {
+    iter: vec::IntoIter<(Ref<K>, Ref<V>)>,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (Ref<K>, Ref<V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+
+pub struct Drain<'a, K, V> {
+    iter: vec::Drain<'a, (Ref<K>, Ref<V>)>,
+}
+
+impl<'a, K, V> Iterator for Drain<'a, K, V> {
+    type Item = (Ref<K>, Ref<V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Drain<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Drain<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for Drain<'a, K, V> {}
@@ -0,0 +1,1390 @@
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::hash::DefaultHasher;
+
+use super::*;
+
+#[test]
+fn insert_reports_overwritten_pairs() {
+    let mut bimap = BiBTreeMap::new();
+    assert_eq!(bimap.insert(1, 'x'), Overwritten::Neither);
+    assert_eq!(bimap.insert(1, 'y'), Overwritten::Left(1, 'x'));
+    assert_eq!(bimap.insert(2, 'y'), Overwritten::Right(1, 'y'));
+    assert_eq!(bimap.insert(2, 'y'), Overwritten::Pair(2, 'y'));
+    assert_eq!(bimap.len(), 1);
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn insert_reports_both_overwritten_pairs() {
+    let mut bimap = BiBTreeMap::new();
+    bimap.insert_unchecked(1, "x");
+    bimap.insert_unchecked(7, "a");
+    assert_eq!(bimap.insert(1, "a"), Overwritten::Both((1, "x"), (7, "a")));
+    assert_eq!(bimap.iter().collect::<Vec<_>>(), vec![(&1, &"a")]);
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn insert_no_overwrite_rejects_present_values() {
+    let mut bimap = BiBTreeMap::new();
+    assert_eq!(bimap.insert_no_overwrite(1, 'a'), Ok(()));
+    assert_eq!(bimap.insert_no_overwrite(1, 'b'), Err((1, 'b')));
+    assert_eq!(bimap.insert_no_overwrite(2, 'a'), Err((2, 'a')));
+    assert_eq!(bimap.insert_no_overwrite(1, 'a'), Err((1, 'a')));
+    assert_eq!(bimap.insert_no_overwrite(2, 'b'), Ok(()));
+    assert_eq!(bimap.get_left(&1), Some(&'a'));
+    assert_eq!(bimap.len(), 2);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn collect_keeps_the_last_conflicting_pair() {
+    let bimap: BiHashMap<_, _> = vec![(1, "a"), (2, "b"), (1, "c"), (3, "b")]
+        .into_iter()
+        .collect();
+    assert_eq!(bimap.len(), 2);
+    assert_eq!(bimap.get_left(&1), Some(&"c"));
+    assert_eq!(bimap.get_right(&"b"), Some(&3));
+    assert!(!bimap.contains_left(&2));
+    assert!(!bimap.contains_right(&"a"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn into_iter_yields_every_hashed_pair_once() {
+    let bimap: BiHashMap<_, _> = (0..100).map(|i| (i, -i)).collect();
+    let mut pairs: Vec<_> = bimap.into_iter().collect();
+    pairs.sort();
+    assert_eq!(pairs, (0..100).map(|i| (i, -i)).collect::<Vec<_>>());
+}
+
+#[test]
+fn btree_bimap_iterates_in_left_order() {
+    let bimap: BiBTreeMap<_, _> = [(3, 'a'), (1, 'c'), (2, 'b')].into_iter().collect();
+    assert_eq!(
+        bimap.iter().collect::<Vec<_>>(),
+        vec![(&1, &'c'), (&2, &'b'), (&3, &'a')]
+    );
+    assert_eq!(bimap.get_right(&'a'), Some(&3));
+}
+
+#[test]
+fn into_iter_yields_every_pair_once() {
+    let bimap: BiBTreeMap<_, _> = (0..100).map(|i| (i, -i)).collect();
+    let iter = bimap.into_iter();
+    assert_eq!(iter.len(), 100);
+    let pairs: Vec<_> = iter.collect();
+    assert_eq!(pairs, (0..100).map(|i| (i, -i)).collect::<Vec<_>>());
+}
+
+#[test]
+fn range_queries_cover_inclusive_bounds() {
+    let bimap: BiBTreeMap<_, _> = (1..10).map(|i| (i, 10 * i)).collect();
+    assert_eq!(
+        bimap.range_left(2..=4).collect::<Vec<_>>(),
+        vec![(&2, &20), (&3, &30), (&4, &40)]
+    );
+    assert_eq!(
+        bimap.range_right(20..=40).rev().collect::<Vec<_>>(),
+        vec![(&4, &40), (&3, &30), (&2, &20)]
+    );
+    assert_eq!(bimap.range_left(10..).count(), 0);
+}
+
+#[test]
+fn btree_iter_reverses() {
+    let bimap: BiBTreeMap<_, _> = [(2, 'b'), (3, 'a'), (1, 'c')].into_iter().collect();
+    let forward: Vec<_> = bimap.iter().collect();
+    let mut backward: Vec<_> = bimap.iter().rev().collect();
+    backward.reverse();
+    assert_eq!(forward, backward);
+    assert_eq!(bimap.iter().next_back(), Some((&3, &'a')));
+}
+
+#[test]
+fn extend_mixes_fresh_and_conflicting_pairs() {
+    let mut bimap: BiBTreeMap<_, _> = [(1, 'a'), (2, 'b')].into_iter().collect();
+    bimap.extend([(3, 'c'), (1, 'x'), (4, 'b'), (3, 'd')]);
+    assert_eq!(
+        bimap.iter().collect::<Vec<_>>(),
+        vec![(&1, &'x'), (&3, &'d'), (&4, &'b')]
+    );
+    assert!(bimap.is_valid());
+
+    let other: BiBTreeMap<_, _> = [(5, 'e'), (1, 'y')].into_iter().collect();
+    bimap.extend(&other);
+    assert_eq!(bimap.get_left(&1), Some(&'y'));
+    assert_eq!(bimap.get_right(&'e'), Some(&5));
+    assert_eq!(bimap.len(), 4);
+}
+
+#[test]
+fn with_capacity_presizes_both_maps() {
+    let bimap: BiSortedVecMap<u32, u32> = BiMap::with_capacity(100);
+    assert!(bimap.capacity() >= 100);
+    assert!(bimap.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn with_capacity_presizes_both_hash_maps() {
+    let bimap: BiHashMap<u32, u32> = BiMap::with_capacity(100);
+    let (lcap, rcap) = bimap.capacities();
+    assert!(lcap >= 100 && rcap >= 100);
+    assert_eq!(bimap.capacity(), lcap.min(rcap));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn reserve_avoids_rehashing() {
+    let mut bimap: BiHashMap<u32, u32> = BiHashMap::new();
+    bimap.reserve(1000);
+    let capacity = bimap.capacities();
+    for i in 0..1000 {
+        bimap.insert_unchecked(i, i);
+    }
+    assert_eq!(bimap.capacities(), capacity);
+
+    bimap.retain(|&l, _| l < 10);
+    bimap.shrink_to_fit();
+    assert!(bimap.capacity() < capacity.0);
+    assert!(bimap.capacity() >= 10);
+}
+
+#[test]
+fn retain_keeps_exactly_the_matching_pairs() {
+    let mut bimap: BiBTreeMap<u32, u32> = (0..1000).map(|i| (i, 1000 - i)).collect();
+    bimap.retain(|&l, &r| l % 3 == 0 && r % 2 == 0);
+    assert_eq!(bimap.len(), 167);
+    assert!(bimap.iter().all(|(&l, &r)| l % 3 == 0 && r % 2 == 0));
+    assert!((0..1000).all(|l| bimap.contains_left(&l) == (l % 6 == 0)));
+    assert!(bimap.is_valid());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn retain_keeps_exactly_the_matching_hashed_pairs() {
+    let mut bimap: BiHashMap<u32, u32> = (0..1000).map(|i| (i, 1000 - i)).collect();
+    bimap.retain(|&l, _| l < 250);
+    assert_eq!(bimap.len(), 250);
+    assert!((0..1000).all(|l| bimap.contains_left(&l) == (l < 250)));
+    assert!((1..=1000).all(|r| bimap.contains_right(&r) == (r > 750)));
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn drain_empties_but_keeps_capacity() {
+    let mut bimap: BiSortedVecMap<u32, u32> = (0..100).map(|i| (i, i)).collect();
+    let capacity = bimap.capacity();
+    let drain = bimap.drain();
+    assert_eq!(drain.len(), 100);
+    assert_eq!(
+        drain.collect::<Vec<_>>(),
+        (0..100).map(|i| (i, i)).collect::<Vec<_>>()
+    );
+    assert!(bimap.is_empty());
+    assert_eq!(bimap.capacity(), capacity);
+}
+
+#[test]
+fn inverse_swaps_the_roles_of_the_values() {
+    let bimap: BiBTreeMap<_, _> = (0..20).map(|i| (i, 100 - i)).collect();
+    let inverse = bimap.clone().inverse();
+    for r in bimap.right_values() {
+        assert_eq!(inverse.get_left(r), bimap.get_right(r));
+    }
+    for (r, l) in bimap.iter_inverse() {
+        assert_eq!(inverse.get_left(r), Some(l));
+    }
+    assert!(inverse.is_valid());
+    assert_eq!(inverse.inverse(), bimap);
+}
+
+#[test]
+fn index_looks_up_either_side() {
+    use alloc::string::{String, ToString};
+
+    let bimap: BiBTreeMap<String, u32> = [("one".to_string(), 1), ("two".to_string(), 2)]
+        .into_iter()
+        .collect();
+    assert_eq!(bimap[Left("two")], 2);
+    assert_eq!(bimap[Left(&"one".to_string())], 1);
+    assert_eq!(bimap[Right(&1)], "one");
+}
+
+#[test]
+#[should_panic(expected = "left value not in bimap")]
+fn index_panics_on_missing_left() {
+    let bimap: BiBTreeMap<_, _> = [(1, 'a')].into_iter().collect();
+    let _ = bimap[Left(&2)];
+}
+
+#[test]
+#[should_panic(expected = "right value not in bimap")]
+fn index_panics_on_missing_right() {
+    let bimap: BiBTreeMap<_, _> = [(1, 'a')].into_iter().collect();
+    let _ = bimap[Right(&'b')];
+}
+
+#[test]
+fn into_values_yield_owned_values() {
+    use alloc::string::{String, ToString};
+
+    let bimap: BiBTreeMap<String, u32> = [("b".to_string(), 2), ("a".to_string(), 1)]
+        .into_iter()
+        .collect();
+    let lefts = bimap.clone().into_left_values();
+    assert_eq!(lefts.len(), 2);
+    assert_eq!(lefts.collect::<Vec<String>>(), vec!["a", "b"]);
+    assert_eq!(
+        bimap.into_right_values().rev().collect::<Vec<_>>(),
+        vec![2, 1]
+    );
+}
+
+#[test]
+fn try_into_inner_needs_a_unique_ref() {
+    let x = Ref::new(5);
+    assert_eq!(Ref::try_into_inner(x), Ok(5));
+
+    let x = Ref::new(5);
+    let y = x.clone();
+    let x = Ref::try_into_inner(x).unwrap_err();
+    assert_eq!(*x, 5);
+    drop(y);
+    assert_eq!(Ref::try_into_inner(x), Ok(5));
+}
+
+#[test]
+fn remove_pair_only_removes_matching_pairs() {
+    let mut bimap: BiBTreeMap<_, _> = [(1, 'a'), (2, 'b')].into_iter().collect();
+    assert_eq!(bimap.remove_pair(&1, &'b'), None);
+    assert_eq!(bimap.remove_pair(&3, &'a'), None);
+    assert_eq!(bimap.len(), 2);
+    assert_eq!(bimap.remove_pair(&1, &'a'), Some((1, 'a')));
+    assert_eq!(bimap.len(), 1);
+    assert!(bimap.is_valid());
+}
+
+#[cfg(feature = "std")]
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn equal_bimaps_hash_equally() {
+    let pairs: Vec<(i32, i32)> = (0..100).map(|i| (i, i * 7)).collect();
+    let a: BiHashMap<_, _> = pairs.iter().copied().collect();
+    let b: BiHashMap<_, _> = pairs.iter().rev().copied().collect();
+    assert_eq!(a, b);
+    assert_eq!(hash_of(&a), hash_of(&b));
+
+    let c: BiBTreeMap<_, _> = pairs.iter().rev().copied().collect();
+    let d: BiBTreeMap<_, _> = pairs.iter().copied().collect();
+    assert_eq!(hash_of(&c), hash_of(&d));
+
+    let mut e = a.clone();
+    e.insert_unchecked(0, 1);
+    assert_ne!(hash_of(&a), hash_of(&e));
+}
+
+#[test]
+fn ordered_bimaps_hash_without_std() {
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = PairHasher::default();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let a: BiBTreeMap<_, _> = (0..100).map(|i| (i, i * 7)).collect();
+    let b: BiBTreeMap<_, _> = (0..100).rev().map(|i| (i, i * 7)).collect();
+    assert_eq!(hash_of(&a), hash_of(&b));
+    let c: BiBTreeMap<_, _> = (0..100).map(|i| (i, i * 5)).collect();
+    assert_ne!(hash_of(&a), hash_of(&c));
+}
+
+#[test]
+fn append_merges_and_empties_other() {
+    let mut bimap: BiBTreeMap<_, _> = [(1, 'a'), (2, 'b')].into_iter().collect();
+    let mut other: BiBTreeMap<_, _> = [(3, 'c'), (4, 'd')].into_iter().collect();
+    bimap.append(&mut other);
+    assert_eq!(bimap.len(), 4);
+    assert!(other.is_empty());
+
+    // A left conflict and a right conflict, both won by `other`.
+    let mut other: BiBTreeMap<_, _> = [(1, 'x'), (5, 'b')].into_iter().collect();
+    bimap.append(&mut other);
+    assert!(other.is_empty());
+    assert_eq!(
+        bimap.iter().collect::<Vec<_>>(),
+        vec![(&1, &'x'), (&3, &'c'), (&4, &'d'), (&5, &'b')]
+    );
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn split_off_keeps_both_halves_valid() {
+    let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, 9 - i)).collect();
+    let upper = bimap.split_off(&5);
+    assert!(bimap.is_valid());
+    assert!(upper.is_valid());
+    assert_eq!(
+        bimap.left_values().copied().collect::<Vec<_>>(),
+        vec![0, 1, 2, 3, 4]
+    );
+    assert_eq!(
+        upper.left_values().copied().collect::<Vec<_>>(),
+        vec![5, 6, 7, 8, 9]
+    );
+    assert!((5..10).all(|r| bimap.contains_right(&r) && !upper.contains_right(&r)));
+    assert!((0..5).all(|r| upper.get_right(&r) == Some(&(9 - r))));
+}
+
+#[test]
+fn retain_one_side_cleans_up_the_other() {
+    let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, 10 * i)).collect();
+    bimap.retain_left(|&l| l % 2 == 0);
+    assert!(!bimap.contains_right(&10));
+    assert!(bimap.contains_right(&20));
+    bimap.retain_right(|&r| r < 50);
+    assert!(!bimap.contains_left(&6));
+    assert_eq!(
+        bimap.left_values().copied().collect::<Vec<_>>(),
+        vec![0, 2, 4]
+    );
+    assert!(bimap.is_valid());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn mixed_backends_stay_consistent() {
+    let mut bimap: BiHashBTreeMap<u32, char> = BiHashBTreeMap::new();
+    for (l, r) in [(3, 'c'), (1, 'a'), (2, 'b'), (4, 'd')] {
+        bimap.insert_unchecked(l, r);
+    }
+    assert_eq!(bimap.insert(5, 'a'), Overwritten::Right(1, 'a'));
+    assert_eq!(bimap.remove_left(&2), Some((2, 'b')));
+    assert_eq!(bimap.remove_right(&'d'), Some((4, 'd')));
+    assert_eq!(
+        bimap.range_right(..).collect::<Vec<_>>(),
+        vec![(&5, &'a'), (&3, &'c')]
+    );
+    let mut lefts: Vec<_> = bimap.left_values().copied().collect();
+    lefts.sort();
+    assert_eq!(lefts, vec![3, 5]);
+    assert!(bimap.is_valid());
+
+    let mut bimap: GenericBiMap<u32, char, BTreeMapKind, HashMapKind> = BiMap::new();
+    bimap.extend([(2, 'b'), (1, 'a')]);
+    assert_eq!(bimap.remove_right(&'a'), Some((1, 'a')));
+    assert_eq!(bimap.first(), Some((&2, &'b')));
+    assert!(bimap.is_valid());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn mixed_backends_shrink_the_sparse_map() {
+    let mut bimap: BiHashBTreeMap<u32, u32> = (0..1000).map(|i| (i, i)).collect();
+    for i in 10..1000 {
+        bimap.remove_left(&i);
+    }
+    let before = bimap.capacities().0;
+    bimap.compact();
+    assert!(bimap.capacities().0 < before);
+    assert_eq!(bimap.capacities().1, 10);
+
+    let mut bimap: BiHashBTreeMap<u32, u32> = (0..1000).map(|i| (i, i)).collect();
+    bimap.enable_auto_shrink(true);
+    for i in 10..1000 {
+        bimap.remove_left(&i);
+    }
+    assert!(bimap.capacities().0 < 100);
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn ordered_bimaps_compare_lexicographically() {
+    let a: BiBTreeMap<_, _> = [(1, 'a'), (2, 'b'), (3, 'c')].into_iter().collect();
+    let b: BiBTreeMap<_, _> = [(1, 'a'), (2, 'x'), (3, 'c')].into_iter().collect();
+    assert!(a < b);
+    assert_eq!(a.cmp(&b), Ordering::Less);
+    assert_eq!(b.partial_cmp(&a), Some(Ordering::Greater));
+    assert_eq!(a.cmp(&a.clone()), Ordering::Equal);
+
+    let prefix: BiBTreeMap<_, _> = [(1, 'a'), (2, 'b')].into_iter().collect();
+    assert!(prefix < a);
+    assert!(BiBTreeMap::new() < prefix);
+}
+
+#[test]
+fn get_full_returns_the_stored_values() {
+    use alloc::string::{String, ToString};
+
+    let bimap: BiBTreeMap<String, String> = [("key".to_string(), "value".to_string())]
+        .into_iter()
+        .collect();
+    let (l, r): (&String, &String) = bimap.get_left_full("key").unwrap();
+    assert_eq!((l.as_str(), r.as_str()), ("key", "value"));
+    let (l, r) = bimap.get_right_full("value").unwrap();
+    assert_eq!((l.as_str(), r.as_str()), ("key", "value"));
+    assert_eq!(bimap.get_left_full("value"), None);
+}
+
+#[test]
+fn try_reserve_succeeds_for_reasonable_sizes() {
+    let mut bimap: BiSortedVecMap<u32, u32> = BiSortedVecMap::new();
+    assert!(bimap.try_reserve(1000).is_ok());
+    assert!(bimap.capacity() >= 1000);
+    assert!(bimap.try_reserve(usize::MAX).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn try_reserve_succeeds_for_reasonable_hashed_sizes() {
+    let mut bimap: BiHashMap<u32, u32> = BiHashMap::new();
+    assert!(bimap.try_reserve(1000).is_ok());
+    assert!(bimap.capacity() >= 1000);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn clone_from_reuses_capacity() {
+    let source: BiHashMap<u32, u32> = (0..500).map(|i| (i, i + 1)).collect();
+    let mut target: BiHashMap<u32, u32> = BiMap::with_capacity(1000);
+    let capacities = target.capacities();
+    target.clone_from(&source);
+    assert_eq!(target.capacities(), capacities);
+    assert_eq!(target, source);
+    assert!(target.is_valid());
+}
+
+#[test]
+fn extract_if_stops_when_dropped() {
+    let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, -i)).collect();
+    let mut iter = bimap.extract_if(|&l, _| l % 2 == 0);
+    assert_eq!(iter.next(), Some((0, 0)));
+    assert_eq!(iter.next(), Some((2, -2)));
+    drop(iter);
+    assert_eq!(bimap.len(), 8);
+    assert!(bimap.contains_left(&4) && bimap.contains_right(&-4));
+    assert!(!bimap.contains_right(&-2));
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn extract_if_removes_every_match() {
+    let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, -i)).collect();
+    let extracted: Vec<_> = bimap.extract_if(|_, &r| r <= -5).collect();
+    assert_eq!(extracted, (5..10).map(|i| (i, -i)).collect::<Vec<_>>());
+    assert_eq!(
+        bimap.left_values().copied().collect::<Vec<_>>(),
+        vec![0, 1, 2, 3, 4]
+    );
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn shrink_to_keeps_the_requested_capacity() {
+    let mut bimap: BiSortedVecMap<u32, u32> = BiMap::with_capacity(1000);
+    bimap.extend((0..10).map(|i| (i, i)));
+    bimap.shrink_to(100);
+    assert!(bimap.capacity() >= 100 && bimap.capacity() < 1000);
+    bimap.shrink_to(0);
+    assert!(bimap.capacity() >= bimap.len());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn shrink_to_keeps_the_requested_hashed_capacity() {
+    let mut bimap: BiHashMap<u32, u32> = BiMap::with_capacity(1000);
+    bimap.extend((0..10).map(|i| (i, i)));
+    bimap.shrink_to(100);
+    assert!(bimap.capacity() >= 100 && bimap.capacity() < 1000);
+    bimap.shrink_to(0);
+    assert!(bimap.capacity() >= 10);
+}
+
+#[test]
+fn from_iter_no_overwrite_reports_rejected_pairs() {
+    let pairs = (0..10).map(|i| (i, i)).chain((0..10).map(|i| (i, i + 100)));
+    let (bimap, rejected): (BiBTreeMap<_, _>, _) = BiMap::from_iter_no_overwrite(pairs);
+    assert_eq!(bimap.len(), 10);
+    assert_eq!(rejected, (0..10).map(|i| (i, i + 100)).collect::<Vec<_>>());
+
+    let pairs = [(1, 'a'), (2, 'a'), (1, 'b'), (3, 'c')];
+    let (bimap, rejected): (BiBTreeMap<_, _>, _) = BiMap::from_iter_no_overwrite(pairs);
+    assert_eq!(rejected, vec![(2, 'a'), (1, 'b')]);
+    assert_eq!(bimap.get_left(&1), Some(&'a'));
+    assert_eq!(bimap.get_left(&3), Some(&'c'));
+}
+
+#[test]
+fn is_valid_accepts_consistent_maps() {
+    let mut bimap = BiBTreeMap::new();
+    assert!(bimap.is_valid());
+    bimap.extend([(1, 'a'), (2, 'b'), (3, 'c')]);
+    assert!(bimap.is_valid());
+    bimap.remove_left(&2);
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn is_valid_rejects_corrupted_maps() {
+    // A pair missing from the right map.
+    let mut bimap: BiBTreeMap<i32, char> = BiBTreeMap::new();
+    let (l0, _) = Ref::split(1);
+    let (r0, _) = Ref::split('a');
+    bimap.lmap.insert(l0, r0);
+    assert!(!bimap.is_valid());
+
+    // Both maps hold the pair, but the values aren't shared between them.
+    let mut bimap: BiBTreeMap<i32, char> = BiBTreeMap::new();
+    let (l0, l1) = Ref::split(1);
+    let (r0, _) = Ref::split('a');
+    let (r1, _) = Ref::split('a');
+    bimap.lmap.insert(l0, r0);
+    bimap.rmap.insert(r1, l1);
+    assert!(!bimap.is_valid());
+
+    // The right map pairs the right value with a different left value.
+    let mut bimap: BiBTreeMap<i32, char> = BiBTreeMap::new();
+    bimap.extend([(1, 'a'), (2, 'b')]);
+    let l = bimap.clone_left_ref(&2).unwrap();
+    let r = bimap.clone_right_ref(&'a').unwrap();
+    bimap.rmap.insert(r, l);
+    assert!(!bimap.is_valid());
+}
+
+#[test]
+fn get_many_left_fetches_several_values() {
+    let bimap: BiBTreeMap<_, _> = [(1, 'a'), (2, 'b'), (3, 'c')].into_iter().collect();
+    assert_eq!(
+        bimap.get_many_left([&3, &7, &1]),
+        [Some(&'c'), None, Some(&'a')]
+    );
+}
+
+#[test]
+fn string_keys_accept_every_reference_level() {
+    use alloc::string::{String, ToString};
+
+    let bimap: BiBTreeMap<String, String> = [("left".to_string(), "right".to_string())]
+        .into_iter()
+        .collect();
+    let owned: String = "left".to_string();
+    let by_ref: &String = &owned;
+    let by_str: &str = "left";
+    assert_eq!(bimap.get_left(&owned).map(String::as_str), Some("right"));
+    assert_eq!(bimap.get_left(by_ref).map(String::as_str), Some("right"));
+    assert_eq!(bimap.get_left(by_str).map(String::as_str), Some("right"));
+    assert_eq!(bimap.get_right("right").map(String::as_str), Some("left"));
+    assert!(bimap.contains_left("left") && !bimap.contains_left(""));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn hashed_string_keys_accept_every_reference_level() {
+    use alloc::string::{String, ToString};
+
+    let bimap: BiHashMap<String, u32> = [("one".to_string(), 1)].into_iter().collect();
+    let owned = "one".to_string();
+    assert_eq!(bimap.get_left(&owned), Some(&1));
+    assert_eq!(bimap.get_left(&owned[..]), Some(&1));
+    assert_eq!(bimap.get_left("one"), Some(&1));
+    assert_eq!(bimap.get_right(&1).map(String::as_str), Some("one"));
+}
+
+#[test]
+fn reserve_balanced_evens_out_the_capacities() {
+    let bimap: BiSortedVecMap<i32, i32> = (0..10).map(|i| (i, -i)).collect();
+    let (mut lmap, rmap) = bimap.into_parts();
+    lmap.reserve(100);
+    let mut bimap = BiMap::from_parts(lmap, rmap).ok().unwrap();
+    let (lcap, rcap) = bimap.capacities();
+    assert!(lcap > rcap);
+
+    bimap.reserve_balanced(5);
+    let (lcap, rcap) = bimap.capacities();
+    assert_eq!(lcap, rcap);
+    assert!(bimap.capacity() >= 15);
+}
+
+#[test]
+fn iter_refs_share_the_stored_values() {
+    let mut bimap = BiBTreeMap::new();
+    bimap.extend([(1, 'a'), (2, 'b'), (3, 'c')]);
+    let index: Vec<Ref<char>> = bimap.iter_refs().map(|(_, r)| r.clone()).collect();
+    assert!(index
+        .iter()
+        .zip(bimap.iter_refs())
+        .all(|(r0, (_, r1))| Ref::ptr_eq(r0, r1)));
+    assert!(!bimap.is_valid());
+
+    drop(index);
+    assert!(bimap.is_valid());
+    assert_eq!(bimap.remove_left(&2), Some((2, 'b')));
+}
+
+#[test]
+fn remove_survives_a_held_iter_ref() {
+    let mut bimap = BiBTreeMap::new();
+    bimap.extend([(1, 'a'), (2, 'b')]);
+    let held = bimap.iter_refs().next().map(|(l, _)| l.clone()).unwrap();
+    assert_eq!(bimap.remove_left(&2), Some((2, 'b')));
+    assert_eq!(bimap.remove_left(&*held), Some((1, 'a')));
+    assert_eq!(*held, 1);
+    assert!(bimap.is_empty());
+}
+
+#[test]
+fn remove_shared_tolerates_outstanding_refs() {
+    let mut bimap = BiBTreeMap::new();
+    bimap.extend([(1, 'a'), (2, 'b')]);
+    let extra = bimap.clone_left_ref(&1).unwrap();
+    let (l, r) = bimap.remove_left_shared(&1).unwrap();
+    assert_eq!(bimap.len(), 1);
+    assert_eq!(Ref::try_into_inner(r), Ok('a'));
+    let l = Ref::try_into_inner(l).unwrap_err();
+    drop(extra);
+    assert_eq!(Ref::try_into_inner(l).ok(), Some(1));
+}
+
+fn exact_len<I: ExactSizeIterator>(iter: I) -> usize {
+    let (lower, upper) = iter.size_hint();
+    assert_eq!(Some(lower), upper);
+    iter.len()
+}
+
+#[test]
+fn ordered_iter_is_double_ended_and_exact() {
+    fn back<I: DoubleEndedIterator>(mut iter: I) -> Option<I::Item> {
+        iter.next_back()
+    }
+
+    let bimap: BiBTreeMap<_, _> = [(1, 'a'), (2, 'b'), (3, 'c')].into_iter().collect();
+    assert_eq!(exact_len(bimap.iter()), 3);
+    assert_eq!(back(bimap.iter()), Some((&3, &'c')));
+    let mut iter = bimap.iter();
+    iter.next();
+    assert_eq!(exact_len(iter), 2);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn hashed_iter_is_exact() {
+    let bimap: BiHashMap<_, _> = [(1, 'a'), (2, 'b'), (3, 'c')].into_iter().collect();
+    assert_eq!(exact_len(bimap.iter()), 3);
+    let mut iter = bimap.iter();
+    iter.next();
+    assert_eq!(exact_len(iter), 2);
+}
+
+#[test]
+fn try_insert_only_refuses_right_collisions() {
+    let mut bimap = BiBTreeMap::new();
+    assert_eq!(bimap.try_insert(1, 'a'), Ok(None));
+    assert_eq!(bimap.try_insert(1, 'b'), Ok(Some('a')));
+    assert_eq!(bimap.try_insert(1, 'b'), Ok(Some('b')));
+    assert_eq!(bimap.try_insert(2, 'b'), Err((2, 'b')));
+    bimap.insert_unchecked(2, 'c');
+    assert_eq!(bimap.try_insert(1, 'c'), Err((1, 'c')));
+    assert_eq!(bimap.get_left(&1), Some(&'b'));
+    assert_eq!(bimap.get_right(&'c'), Some(&2));
+    assert!(!bimap.contains_right(&'a'));
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn get_ref_returns_the_stored_refs() {
+    let mut bimap = BiBTreeMap::new();
+    bimap.extend([(1, 'a'), (2, 'b')]);
+    let r = bimap.get_left_ref(&1).unwrap();
+    let l = bimap.get_right_ref(&'a').unwrap();
+    assert_eq!((**l, **r), (1, 'a'));
+    assert!(Ref::ptr_eq(r, bimap.iter_refs().next().unwrap().1));
+    assert!(bimap.get_left_ref(&3).is_none());
+    assert!(bimap.get_right_ref(&'c').is_none());
+
+    let shared = r.clone();
+    assert!(!bimap.is_valid());
+    drop(shared);
+    assert_eq!(bimap.remove_left(&1), Some((1, 'a')));
+}
+
+#[test]
+fn insert_iter_counts_the_displaced_pairs() {
+    let mut bimap: BiSortedVecMap<i32, i32> = BiMap::new();
+    let fresh = (0..950).map(|i| (i, i));
+    let colliding = (0..50).map(|i| (i * 19, 1000 + i));
+    assert_eq!(bimap.insert_iter(fresh.chain(colliding)), 50);
+    assert_eq!(bimap.len(), 950);
+    assert_eq!(bimap.get_left(&19), Some(&1001));
+
+    assert_eq!(bimap.insert_iter([(19, 1001), (1, 1)]), 0);
+    assert!(bimap.is_valid());
+}
+
+fn set_ops_operands() -> (BiBTreeMap<i32, char>, BiBTreeMap<i32, char>) {
+    let a = [(1, 'a'), (2, 'b'), (3, 'c')].into_iter().collect();
+    let b = [(1, 'a'), (2, 'x'), (4, 'd'), (5, 'c')]
+        .into_iter()
+        .collect();
+    (a, b)
+}
+
+#[test]
+fn intersection_keeps_the_shared_pairs() {
+    let (a, b) = set_ops_operands();
+    let both = a.intersection(&b);
+    assert_eq!(both.iter().collect::<Vec<_>>(), vec![(&1, &'a')]);
+    assert_eq!(b.intersection(&a), both);
+    assert!(both.is_valid());
+}
+
+#[test]
+fn union_skips_conflicting_pairs() {
+    let (a, b) = set_ops_operands();
+    // (2, 'x') and (5, 'c') would each pair a value of `a` a second time.
+    let either = a.union(&b);
+    assert_eq!(
+        either.iter().collect::<Vec<_>>(),
+        vec![(&1, &'a'), (&2, &'b'), (&3, &'c'), (&4, &'d')]
+    );
+    assert!(either.is_valid());
+}
+
+#[test]
+fn symmetric_difference_skips_conflicting_pairs() {
+    let (a, b) = set_ops_operands();
+    let one = a.symmetric_difference(&b);
+    assert_eq!(
+        one.iter().collect::<Vec<_>>(),
+        vec![(&2, &'b'), (&3, &'c'), (&4, &'d')]
+    );
+    assert!(one.is_valid());
+    assert!(a.symmetric_difference(&a).is_empty());
+}
+
+#[test]
+fn next_after_steps_through_the_keys() {
+    let bimap: BiBTreeMap<_, _> = [(10, 'c'), (20, 'a'), (30, 'b')].into_iter().collect();
+    let mut lefts = Vec::new();
+    let mut next = bimap.first();
+    while let Some((l, _)) = next {
+        lefts.push(*l);
+        next = bimap.next_left_after(l);
+    }
+    assert_eq!(lefts, vec![10, 20, 30]);
+    assert_eq!(bimap.next_left_after(&15), Some((&20, &'a')));
+    assert_eq!(bimap.next_left_after(&30), None);
+    assert_eq!(bimap.next_right_after(&'a'), Some((&30, &'b')));
+    assert_eq!(bimap.next_right_after(&'c'), None);
+}
+
+#[test]
+fn clear_and_shrink_frees_the_capacity() {
+    let mut bimap: BiSortedVecMap<u32, u32> = (0..1000).map(|i| (i, i)).collect();
+    bimap.clear_and_shrink();
+    assert!(bimap.is_empty());
+    assert_eq!(bimap.capacities(), (0, 0));
+
+    bimap.extend((0..1000).map(|i| (i, i)));
+    bimap.reset_to_capacity(100);
+    assert!(bimap.is_empty());
+    let (lcap, rcap) = bimap.capacities();
+    assert!((100..1000).contains(&lcap));
+    assert!((100..1000).contains(&rcap));
+}
+
+#[test]
+fn get_either_reports_which_side_matched() {
+    let bimap: BiBTreeMap<u32, u32> = [(1, 10), (2, 20), (10, 30)].into_iter().collect();
+    assert_eq!(bimap.get_either(&2), Some(Either::Left(&20)));
+    assert_eq!(bimap.get_either(&20), Some(Either::Right(&2)));
+    // The left map is checked first.
+    assert_eq!(bimap.get_either(&10), Some(Either::Left(&30)));
+    assert_eq!(bimap.get_either(&5), None);
+}
+
+// These only need to compile: a missing impl is caught at build time.
+fn assert_exact_fused<I: ExactSizeIterator + core::iter::FusedIterator>(_: I) {}
+
+fn assert_fused<I: core::iter::FusedIterator>(_: I) {}
+
+#[test]
+fn iterators_are_fused_and_exact_where_possible() {
+    let mut bimap: BiBTreeMap<i32, char> = [(1, 'a'), (2, 'b')].into_iter().collect();
+    assert_exact_fused(bimap.iter());
+    assert_exact_fused(bimap.iter_inverse());
+    assert_exact_fused(bimap.pairs());
+    assert_exact_fused(bimap.left_values());
+    assert_exact_fused(bimap.right_values());
+    assert_exact_fused(bimap.cloned_left_values());
+    assert_exact_fused(bimap.left_map().iter());
+    assert_fused(bimap.range_left(..));
+    assert_fused(bimap.range_right(..));
+    assert_exact_fused(bimap.clone().into_iter());
+    assert_exact_fused(bimap.clone().into_left_values());
+    assert_exact_fused(bimap.clone().into_right_values());
+    assert_exact_fused(bimap.drain_left_range(..1));
+    let ordered: InsertionOrdered<_, _> = bimap.clone().into();
+    assert_exact_fused(ordered.iter_ordered());
+    assert_fused(bimap.extract_if(|_, _| false));
+    assert_exact_fused(bimap.drain());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn hashed_iterators_are_fused_and_exact_where_possible() {
+    let mut bimap: BiHashMap<i32, char> = [(1, 'a'), (2, 'b')].into_iter().collect();
+    assert_exact_fused(bimap.iter());
+    assert_exact_fused(bimap.pairs());
+    assert_exact_fused(bimap.clone().into_iter());
+    assert_fused(bimap.extract_if(|_, _| false));
+    assert_exact_fused(bimap.drain());
+}
+
+#[test]
+fn replace_right_replaces_the_paired_value() {
+    let mut bimap: BiBTreeMap<_, _> = [(1, 'a'), (2, 'b')].into_iter().collect();
+    assert_eq!(bimap.replace_right(&1, 'c'), Ok('a'));
+    assert_eq!(bimap.get_left(&1), Some(&'c'));
+    assert!(!bimap.contains_right(&'a'));
+    assert_eq!(bimap.replace_right(&1, 'c'), Ok('c'));
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn replace_right_rejects_absent_left() {
+    let mut bimap: BiBTreeMap<_, _> = [(1, 'a')].into_iter().collect();
+    let err = bimap.replace_right(&3, 'z').unwrap_err();
+    assert_eq!(err, ReplaceRightError::LeftNotFound('z'));
+    assert_eq!(err.into_inner(), 'z');
+    assert_eq!(bimap.len(), 1);
+}
+
+#[test]
+fn replace_right_rejects_right_collision() {
+    let mut bimap: BiBTreeMap<_, _> = [(1, 'a'), (2, 'b')].into_iter().collect();
+    assert_eq!(
+        bimap.replace_right(&1, 'b'),
+        Err(ReplaceRightError::RightCollision('b'))
+    );
+    assert_eq!(bimap.get_left(&1), Some(&'a'));
+    assert_eq!(bimap.get_left(&2), Some(&'b'));
+}
+
+fn pair_parts(
+    pairs: Vec<(i32, char)>,
+) -> (
+    btree_map::BTreeMap<i32, char>,
+    btree_map::BTreeMap<char, i32>,
+) {
+    let mut lmap = btree_map::BTreeMap::new();
+    let mut rmap = btree_map::BTreeMap::new();
+    for (left, right) in pairs {
+        let (l0, r0) = (Ref::new(left), Ref::new(right));
+        rmap.insert(r0.clone(), l0.clone());
+        lmap.insert(l0, r0);
+    }
+    (lmap, rmap)
+}
+
+#[test]
+fn from_parts_accepts_valid_parts() {
+    let (lmap, rmap) = pair_parts(vec![(1, 'a'), (2, 'b')]);
+    let bimap = BiMap::from_parts(lmap, rmap).ok().unwrap();
+    assert_eq!(bimap.get_left(&2), Some(&'b'));
+    assert_eq!(bimap.get_right(&'a'), Some(&1));
+
+    let (lmap, rmap) = bimap.into_parts();
+    let mut bimap = BiMap::from_parts(lmap, rmap).ok().unwrap();
+    assert_eq!(bimap.remove_left(&1), Some((1, 'a')));
+}
+
+#[test]
+fn from_parts_rejects_mismatched_parts() {
+    let (lmap, _) = pair_parts(vec![(1, 'a'), (2, 'b')]);
+    let (_, rmap) = pair_parts(vec![(1, 'a'), (2, 'b')]);
+    let result = BiMap::from_parts(lmap, rmap);
+    let (lmap, rmap) = result.err().unwrap();
+    assert_eq!((lmap.len(), rmap.len()), (2, 2));
+
+    let (lmap, _) = pair_parts(vec![(1, 'a'), (2, 'b')]);
+    let (_, rmap) = pair_parts(vec![(1, 'a')]);
+    let result = BiMap::from_parts(lmap, rmap);
+    assert!(result.is_err());
+}
+
+#[test]
+fn cloned_values_leave_the_bimap_intact() {
+    use alloc::string::String;
+
+    let mut bimap: BiBTreeMap<String, u32> = BiMap::new();
+    bimap.insert_unchecked("one".into(), 1);
+    bimap.insert_unchecked("two".into(), 2);
+    let lefts: Vec<String> = bimap.cloned_left_values().collect();
+    let rights: Vec<u32> = bimap.cloned_right_values().collect();
+    assert_eq!(lefts, vec!["one", "two"]);
+    assert_eq!(rights, vec![1, 2]);
+
+    bimap.insert_unchecked("three".into(), 3);
+    assert_eq!(bimap.remove_left("one"), Some(("one".into(), 1)));
+    assert!(bimap.is_valid());
+    assert_eq!(lefts.len(), 2);
+}
+
+#[test]
+fn retain_ordered_counts_in_left_order() {
+    // Insert out of order so that the positions can't come from insertion.
+    let mut bimap: BiBTreeMap<i32, i32> = (0..10).rev().map(|i| (i, 10 * i)).collect();
+    bimap.retain_ordered(|index, _, _| index % 2 == 0);
+    assert_eq!(
+        bimap.iter().collect::<Vec<_>>(),
+        vec![(&0, &0), (&2, &20), (&4, &40), (&6, &60), (&8, &80)]
+    );
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn update_rights_transforms_every_value() {
+    let mut bimap: BiBTreeMap<i32, i32> = (0..5).map(|i| (i, i)).collect();
+    let rejected = bimap.update_rights(|&l, r| r * 10 + l);
+    assert!(rejected.is_empty());
+    assert_eq!(
+        bimap.iter().collect::<Vec<_>>(),
+        vec![(&0, &0), (&1, &11), (&2, &22), (&3, &33), (&4, &44)]
+    );
+    assert_eq!(bimap.get_right(&33), Some(&3));
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn update_rights_rejects_the_later_of_colliding_pairs() {
+    let mut bimap: BiBTreeMap<i32, i32> = (0..4).map(|i| (i, i)).collect();
+    let rejected = bimap.update_rights(|_, r| r / 2);
+    assert_eq!(rejected, vec![(1, 0), (3, 1)]);
+    assert_eq!(bimap.iter().collect::<Vec<_>>(), vec![(&0, &0), (&2, &1)]);
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn auto_shrink_after_retain() {
+    assert_shrinks(|bimap| bimap.retain(|&l, _| l < 10));
+}
+
+#[test]
+fn cloned_refs_point_at_the_stored_values() {
+    let mut bimap = BiBTreeMap::new();
+    bimap.extend([(1, 'a'), (2, 'b'), (3, 'c')]);
+    let index: Vec<(Ref<i32>, Ref<char>)> = [1, 3]
+        .iter()
+        .map(|l| {
+            let l = bimap.clone_left_ref(l).unwrap();
+            let r = bimap.clone_right_ref(bimap.get_left(&*l).unwrap()).unwrap();
+            (l, r)
+        })
+        .collect();
+    for (l, r) in &index {
+        let (l0, r0) = bimap.lmap.get_key_value(&**l).unwrap();
+        assert!(Rc::ptr_eq(&l.ptr, &l0.ptr));
+        assert!(Rc::ptr_eq(&r.ptr, &r0.ptr));
+    }
+    assert!(bimap.clone_left_ref(&4).is_none());
+    assert!(bimap.clone_right_ref(&'d').is_none());
+
+    // Pairs outside the index can still be removed.
+    assert_eq!(bimap.remove_left(&2), Some((2, 'b')));
+    drop(index);
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn partition_splits_into_valid_halves() {
+    let bimap: BiBTreeMap<i32, i32> = (0..10).map(|i| (i, 100 - i)).collect();
+    let (even, odd) = bimap.clone().partition(|_, r| r % 2 == 0);
+    assert_eq!(even.len(), 5);
+    assert!(even.right_values().all(|r| r % 2 == 0));
+    assert!(odd.right_values().all(|r| r % 2 != 0));
+    assert!(even.is_valid() && odd.is_valid());
+
+    let mut whole = even;
+    whole.extend(odd);
+    assert_eq!(whole, bimap);
+}
+
+#[test]
+fn contains_each_checks_every_value_in_order() {
+    let bimap: BiBTreeMap<i32, char> = [(1, 'a'), (3, 'c')].into_iter().collect();
+    assert_eq!(
+        bimap.contains_left_each(&[&3, &2, &1, &1]),
+        vec![true, false, true, true]
+    );
+    assert_eq!(bimap.contains_right_each(&[&'b', &'c']), vec![false, true]);
+    assert!(bimap.contains_left_each(&[]).is_empty());
+}
+
+#[test]
+fn std_style_aliases_match_the_originals() {
+    let bimap: BiBTreeMap<i32, char> = [(2, 'b'), (1, 'a'), (3, 'c')].into_iter().collect();
+    assert!(bimap.pairs().eq(bimap.iter()));
+    assert!(bimap.pairs().rev().eq(bimap.iter().rev()));
+    assert!(bimap.left_keys().eq(bimap.left_values()));
+    assert_eq!(bimap.left_keys().collect::<Vec<_>>(), vec![&1, &2, &3]);
+}
+
+struct Reverse;
+
+impl<T: ?Sized + Ord> Comparator<T> for Reverse {
+    fn compare(a: &T, b: &T) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+struct CaseInsensitive;
+
+impl Comparator<&str> for CaseInsensitive {
+    fn compare(a: &&str, b: &&str) -> Ordering {
+        let lower = |s: &str| {
+            s.bytes()
+                .map(|b| b.to_ascii_lowercase())
+                .collect::<Vec<_>>()
+        };
+        lower(a).cmp(&lower(b))
+    }
+}
+
+#[test]
+fn sorted_vec_backend_uses_a_reverse_comparator() {
+    let mut bimap: BiSortedVecMap<i32, char, Reverse> =
+        [(1, 'a'), (3, 'c'), (2, 'b')].into_iter().collect();
+    assert_eq!(bimap.left_values().collect::<Vec<_>>(), vec![&3, &2, &1]);
+    assert_eq!(
+        bimap.right_values().collect::<Vec<_>>(),
+        vec![&'c', &'b', &'a']
+    );
+    assert_eq!(bimap.first(), Some((&3, &'c')));
+    assert_eq!(bimap.next_left_after(&3), Some((&2, &'b')));
+    assert_eq!(bimap.range_left(..=2).count(), 2);
+    assert_eq!(bimap.remove_left(&2), Some((2, 'b')));
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn sorted_vec_backend_uses_a_case_insensitive_comparator() {
+    let mut bimap: BiSortedVecMap<&str, i32, CaseInsensitive> = BiMap::new();
+    bimap.insert_unchecked("b", 2);
+    bimap.insert_unchecked("A", 1);
+    bimap.insert_unchecked("C", 3);
+    assert_eq!(
+        bimap.left_values().collect::<Vec<_>>(),
+        vec![&"A", &"b", &"C"]
+    );
+    assert_eq!(bimap.get_left(&"a"), Some(&1));
+    assert!(bimap.contains_left(&"B"));
+    assert_eq!(bimap.insert("c", 4), Overwritten::Left("C", 3));
+    assert_eq!(bimap.get_right(&4), Some(&"c"));
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn remove_by_ref_drops_the_ref_first() {
+    let mut bimap = BiBTreeMap::new();
+    bimap.extend([(1, 'a'), (2, 'b'), (3, 'c')]);
+    let left = bimap.clone_left_ref(&1).unwrap();
+    assert_eq!(bimap.remove_left_ref(left), Some((1, 'a')));
+    let right = bimap.clone_right_ref(&'c').unwrap();
+    assert_eq!(bimap.remove_right_ref(right), Some((3, 'c')));
+    assert_eq!(bimap.remove_left_ref(Ref::new(1)), None);
+    assert_eq!(bimap.iter().collect::<Vec<_>>(), vec![(&2, &'b')]);
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn memory_usage_grows_with_the_pairs() {
+    let mut bimap: BiSortedVecMap<u64, u32> = BiMap::new();
+    let mut usage = bimap.memory_usage();
+    for i in 0..200 {
+        bimap.insert_unchecked(i, i as u32);
+        let next = bimap.memory_usage();
+        assert!(next >= usage);
+        assert!(next > bimap.len() * (mem::size_of::<u64>() + mem::size_of::<u32>()));
+        usage = next;
+    }
+}
+
+#[test]
+fn remap_drops_and_transforms_pairs() {
+    let bimap: BiBTreeMap<i32, char> = [(1, 'a'), (2, 'b'), (3, 'c')].into_iter().collect();
+    assert_eq!(bimap.clone().remap(|l, r| Some((l, r))), bimap);
+
+    let odd = bimap.clone().remap(|l, r| (l % 2 != 0).then_some((l, r)));
+    assert_eq!(odd.iter().collect::<Vec<_>>(), vec![(&1, &'a'), (&3, &'c')]);
+
+    let shifted = bimap
+        .clone()
+        .remap(|l, r| Some((l * 10, r.to_ascii_uppercase())));
+    assert_eq!(shifted.get_left(&20), Some(&'B'));
+    assert!(shifted.is_valid());
+}
+
+#[test]
+fn remap_keeps_the_last_colliding_pair() {
+    let bimap: BiBTreeMap<i32, char> = [(1, 'a'), (2, 'b'), (3, 'c')].into_iter().collect();
+    // (1, 'a') and (2, 'b') both move to the left value 0, and then (3, 'c')
+    // takes 'b' from the pair that survived.
+    let merged = bimap.remap(|l, r| match l {
+        3 => Some((3, 'b')),
+        _ => Some((0, r)),
+    });
+    assert_eq!(merged.iter().collect::<Vec<_>>(), vec![(&3, &'b')]);
+    assert!(merged.is_valid());
+}
+
+#[test]
+fn compact_only_shrinks_sparse_bimaps() {
+    let mut bimap: BiSortedVecMap<u32, u32> = (0..10000).map(|i| (i, i)).collect();
+    bimap.retain(|&l, _| l < 10);
+    assert!(bimap.capacity() >= 10000);
+    bimap.compact();
+    assert!(bimap.capacity() < SPARSE_RATIO * 10);
+    assert_eq!(bimap.len(), 10);
+
+    let mut dense: BiSortedVecMap<u32, u32> = (0..1000).map(|i| (i, i)).collect();
+    let capacities = dense.capacities();
+    dense.compact();
+    assert_eq!(dense.capacities(), capacities);
+}
+
+#[test]
+fn len_left_and_len_right_expose_a_mismatch() {
+    let mut bimap: BiBTreeMap<i32, char> = [(1, 'a'), (2, 'b')].into_iter().collect();
+    assert_eq!((bimap.len_left(), bimap.len_right()), (2, 2));
+    // Break the invariant the way a faulty backend might.
+    bimap.lmap.insert(Ref::new(3), Ref::new('c'));
+    assert_eq!((bimap.len_left(), bimap.len_right()), (3, 2));
+    assert!(!bimap.is_valid());
+}
+
+#[test]
+fn swap_pair_left_exchanges_the_right_values() {
+    let mut bimap: BiBTreeMap<i32, char> = [(1, 'a'), (2, 'b'), (3, 'c')].into_iter().collect();
+    assert!(bimap.swap_pair_left(&1, &3));
+    assert_eq!(
+        bimap.iter().collect::<Vec<_>>(),
+        vec![(&1, &'c'), (&2, &'b'), (&3, &'a')]
+    );
+    assert_eq!(bimap.get_right(&'a'), Some(&3));
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn swap_pair_left_needs_both_values() {
+    let mut bimap: BiBTreeMap<i32, char> = [(1, 'a'), (2, 'b')].into_iter().collect();
+    let before = bimap.clone();
+    assert!(!bimap.swap_pair_left(&1, &5));
+    assert!(!bimap.swap_pair_left(&5, &1));
+    assert_eq!(bimap, before);
+}
+
+#[test]
+fn swap_pair_left_with_itself_does_nothing() {
+    let mut bimap: BiBTreeMap<i32, char> = [(1, 'a'), (2, 'b')].into_iter().collect();
+    let before = bimap.clone();
+    assert!(bimap.swap_pair_left(&1, &1));
+    assert_eq!(bimap, before);
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn drain_left_range_leaves_a_valid_remainder() {
+    let mut bimap: BiBTreeMap<i32, i32> = (0..10).map(|i| (i, 100 + i)).collect();
+    let drained: Vec<_> = bimap.drain_left_range(..=5).collect();
+    assert_eq!(drained, (0..=5).map(|i| (i, 100 + i)).collect::<Vec<_>>());
+    assert_eq!(
+        bimap.iter().collect::<Vec<_>>(),
+        vec![(&6, &106), (&7, &107), (&8, &108), (&9, &109)]
+    );
+    assert!(!bimap.contains_right(&105));
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn drain_right_range_leaves_a_valid_remainder() {
+    let mut bimap: BiBTreeMap<i32, i32> = (0..10).map(|i| (i, 100 - i)).collect();
+    let drained: Vec<_> = bimap.drain_right_range(95..).collect();
+    assert_eq!(drained.len(), 6);
+    assert!(drained.iter().all(|&(l, r)| l <= 5 && r == 100 - l));
+    assert_eq!(
+        bimap.left_values().collect::<Vec<_>>(),
+        vec![&6, &7, &8, &9]
+    );
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn is_valid_rejects_outstanding_refs() {
+    let mut bimap = BiBTreeMap::new();
+    bimap.extend([(1, 'a'), (2, 'b')]);
+    let extra = bimap.clone_right_ref(&'b').unwrap();
+    assert!(!bimap.is_valid());
+    drop(extra);
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn from_parts_rejects_outstanding_refs() {
+    let (lmap, rmap) = pair_parts(vec![(1, 'a'), (2, 'b')]);
+    let extra = lmap.get(&1).unwrap().clone();
+    let result = BiMap::from_parts(lmap, rmap);
+    let (lmap, rmap) = result.err().unwrap();
+    drop(extra);
+    let result = BiMap::from_parts(lmap, rmap);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn remove_survives_outstanding_refs() {
+    let mut bimap = BiBTreeMap::new();
+    bimap.extend([(1, 'a'), (2, 'b'), (3, 'c')]);
+    let left = bimap.clone_left_ref(&1).unwrap();
+    let right = bimap.clone_right_ref(&'b').unwrap();
+    assert_eq!(bimap.remove_left(&1), Some((1, 'a')));
+    assert_eq!(bimap.remove_left(&2), Some((2, 'b')));
+    assert_eq!((*left, *right), (1, 'b'));
+    assert_eq!(bimap.len(), 1);
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn insert_survives_outstanding_refs() {
+    let mut bimap = BiBTreeMap::new();
+    bimap.extend([(1, 'a'), (2, 'b')]);
+    let extra = bimap.clone_left_ref(&1).unwrap();
+    assert_eq!(bimap.insert(1, 'c'), Overwritten::Left(1, 'a'));
+    assert_eq!(*extra, 1);
+    assert_eq!(bimap.get_left(&1), Some(&'c'));
+    assert!(bimap.is_valid());
+}
+
+#[test]
+fn sorted_vec_bimaps_compare_lexicographically() {
+    let a: BiSortedVecMap<_, _> = [(3, 'c'), (1, 'a')].into_iter().collect();
+    let b: BiSortedVecMap<_, _> = [(1, 'a'), (2, 'b')].into_iter().collect();
+    assert!(b < a);
+    assert_eq!(a.cmp(&a.clone()), Ordering::Equal);
+}
+
+fn spiked(auto_shrink: bool) -> BiSortedVecMap<u32, u32> {
+    let mut bimap: BiSortedVecMap<u32, u32> = (0..10_000).map(|i| (i, i)).collect();
+    bimap.enable_auto_shrink(auto_shrink);
+    bimap
+}
+
+fn assert_shrinks(f: impl Fn(&mut BiSortedVecMap<u32, u32>)) {
+    let mut bimap = spiked(true);
+    f(&mut bimap);
+    assert_eq!(bimap.len(), 10);
+    assert!(bimap.capacity() < 10 * SPARSE_RATIO);
+    assert!(bimap.is_valid());
+
+    let mut bimap = spiked(false);
+    f(&mut bimap);
+    assert_eq!(bimap.len(), 10);
+    assert!(bimap.capacity() >= 10_000);
+}
+
+#[test]
+fn auto_shrink_after_remove() {
+    assert_shrinks(|bimap| {
+        for i in 10..5_000 {
+            bimap.remove_left(&i);
+        }
+        for i in 5_000..10_000 {
+            bimap.remove_right(&i);
+        }
+    });
+    assert_shrinks(|bimap| {
+        for i in 10..10_000 {
+            bimap.remove_left_shared(&i);
+        }
+    });
+    assert_shrinks(|bimap| {
+        for i in 10..10_000 {
+            bimap.remove_right_shared(&i);
+        }
+    });
+}
+
+#[test]
+fn auto_shrink_after_pop() {
+    assert_shrinks(|bimap| {
+        for _ in 0..5_000 {
+            bimap.pop_last();
+        }
+        for _ in 0..4_990 {
+            bimap.pop_first();
+        }
+    });
+}
+
+#[test]
+fn auto_shrink_after_extract_if() {
+    assert_shrinks(|bimap| {
+        assert_eq!(bimap.extract_if(|&l, _| l >= 10).count(), 9_990);
+    });
+}
+
+#[test]
+fn auto_shrink_after_split_off_and_drain_range() {
+    assert_shrinks(|bimap| drop(bimap.split_off(&10)));
+    assert_shrinks(|bimap| drop(bimap.drain_left_range(10..)));
+    assert_shrinks(|bimap| drop(bimap.drain_right_range(..9_990)));
+}
+
+#[test]
+fn auto_shrink_after_drain() {
+    let mut bimap = spiked(true);
+    assert_eq!(bimap.drain().len(), 10_000);
+    assert_eq!(bimap.capacity(), 0);
+
+    let mut bimap = spiked(false);
+    assert_eq!(bimap.drain().len(), 10_000);
+    assert!(bimap.capacity() >= 10_000);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn auto_shrink_hash_backend() {
+    let mut bimap: BiHashMap<u32, u32> = (0..10_000).map(|i| (i, i)).collect();
+    bimap.enable_auto_shrink(true);
+    bimap.extract_if(|&l, _| l >= 10).for_each(drop);
+    assert!(bimap.capacity() < 10 * SPARSE_RATIO);
+
+    let mut bimap: BiHashMap<u32, u32> = (0..10_000).map(|i| (i, i)).collect();
+    bimap.enable_auto_shrink(true);
+    let pairs: Vec<_> = bimap.drain().collect();
+    assert_eq!(pairs.len(), 10_000);
+    assert_eq!(bimap.capacity(), 0);
+}
+
+#[test]
+fn clear_keeps_capacity_with_auto_shrink() {
+    let mut bimap = spiked(true);
+    bimap.clear();
+    assert!(bimap.capacity() >= 10_000);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn backend_kinds_can_be_named() {
+    let mut bimap: GenericBiMap<i32, char, BTreeMapKind, HashMapKind> = BiMap::new();
+    bimap.extend([(2, 'b'), (1, 'a')]);
+    assert_eq!(bimap.left_values().collect::<Vec<_>>(), vec![&1, &2]);
+    assert_eq!(bimap.get_right(&'b'), Some(&2));
+}
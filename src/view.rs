@@ -0,0 +1,56 @@
+use crate::{BiMap, Iter, MapBase, MapExt};
+
+/// A read-only view of one direction of a `BiMap`.
+pub struct MapView<'a, M> {
+    map: &'a M,
+}
+
+impl<LMap, RMap> BiMap<LMap, RMap> {
+    /// Returns a view of the bimap as a map from left values to right values.
+    pub fn left_map(&self) -> MapView<'_, LMap> {
+        MapView { map: &self.lmap }
+    }
+
+    /// Returns a view of the bimap as a map from right values to left values.
+    pub fn right_map(&self) -> MapView<'_, RMap> {
+        MapView { map: &self.rmap }
+    }
+}
+
+impl<'a, M: MapBase> MapView<'a, M> {
+    pub fn get<Q: ?Sized>(&self, key: &Q) -> Option<&'a M::Val>
+    where
+        M: MapExt<Q>,
+    {
+        self.map.get(key).map(|v| &**v)
+    }
+
+    pub fn contains_key<Q: ?Sized>(&self, key: &Q) -> bool
+    where
+        M: MapExt<Q>,
+    {
+        self.map.contains(key)
+    }
+
+    pub fn iter(&self) -> Iter<'a, M> {
+        Iter {
+            iter: self.map.iter(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<M> Clone for MapView<'_, M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M> Copy for MapView<'_, M> {}